@@ -1,4 +1,3 @@
-use advent_of_code_2019_rust;
 use criterion::{criterion_group, criterion_main, Criterion};
 use std::fs;
 use std::io;
@@ -160,9 +159,39 @@ fn day_09(c: &mut Criterion) {
     });
 }
 
+fn day_16(c: &mut Criterion) {
+    // no puzzle input is checked in for day 16, so benchmark a generated signal of the same length
+    // its first seven digits place the message offset in the second half of the repeated signal
+    let mut state = 1u32;
+    let file: String = "5970000"
+        .chars()
+        .chain((7..650).map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (b'0' + (state >> 16) as u8 % 10) as char
+        }))
+        .collect();
+    c.bench_function("day_16_part_01", |b| {
+        b.iter(|| {
+            let reader = io::BufReader::new(file.as_bytes());
+            advent_of_code_2019_rust::days::day16::part01(reader, 100).unwrap();
+        })
+    });
+
+    // part 2 works through millions of digits per run, so take fewer samples
+    let mut group = c.benchmark_group("day_16");
+    group.sample_size(10);
+    group.bench_function("day_16_part_02", |b| {
+        b.iter(|| {
+            let reader = io::BufReader::new(file.as_bytes());
+            advent_of_code_2019_rust::days::day16::part02(reader, 100).unwrap();
+        })
+    });
+    group.finish();
+}
+
 criterion_group!(
     name= benches;
     config = Criterion::default();
-    targets = day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09
+    targets = day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09, day_16
 );
 criterion_main!(benches);
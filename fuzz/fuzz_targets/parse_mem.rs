@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use advent_of_code_2019_rust::shared::intcode::{loader, parse_mem};
+
+fuzz_target!(|data: &[u8]| {
+    // anything that parses must survive a round trip through a memory image
+    if let Ok(mem) = parse_mem(data) {
+        let mut image = Vec::new();
+        loader::write_image(&mem, &mut image).unwrap();
+        assert_eq!(loader::read_image(&image).unwrap(), mem);
+    }
+});
@@ -0,0 +1,14 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use advent_of_code_2019_rust::shared::intcode::differential;
+
+/// Instructions each program may execute before it is stopped
+const STEP_BUDGET: usize = 10_000;
+
+fuzz_target!(|data: &[u8]| {
+    let (mem, inputs) = differential::generate(data);
+    if let Err(e) = differential::compare(&mem, &inputs, STEP_BUDGET) {
+        panic!("{}", e);
+    }
+});
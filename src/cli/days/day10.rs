@@ -0,0 +1,26 @@
+use clap::Clap;
+
+/// Day 10: Monitoring Station
+#[derive(Clap)]
+pub struct Day10 {
+    #[clap(subcommand)]
+    pub parts: Parts,
+}
+
+#[derive(Clap)]
+pub enum Parts {
+    Part01(Part01),
+    Part02(Part02),
+}
+
+/// Part 1
+#[derive(Clap)]
+pub struct Part01 {}
+
+/// Part 2
+#[derive(Clap)]
+pub struct Part02 {
+    /// Report the nth asteroid to be vaporized
+    #[clap(short = "n", long = "nth", default_value = "200")]
+    pub nth: usize,
+}
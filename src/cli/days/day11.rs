@@ -0,0 +1,30 @@
+use clap::Clap;
+
+/// Day 11: Space Police
+#[derive(Clap)]
+pub struct Day11 {
+    #[clap(subcommand)]
+    pub parts: Parts,
+}
+
+#[derive(Clap)]
+pub enum Parts {
+    Part01(Part01),
+    Part02(Part02),
+}
+
+/// Part 1
+#[derive(Clap)]
+pub struct Part01 {
+    /// Color of the starting panel, black or white
+    #[clap(short = "s", long = "start", default_value = "black")]
+    pub start: String,
+}
+
+/// Part 2
+#[derive(Clap)]
+pub struct Part02 {
+    /// Color of the starting panel, black or white
+    #[clap(short = "s", long = "start", default_value = "white")]
+    pub start: String,
+}
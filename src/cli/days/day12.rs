@@ -0,0 +1,26 @@
+use clap::Clap;
+
+/// Day 12: The N-Body Problem
+#[derive(Clap)]
+pub struct Day12 {
+    #[clap(subcommand)]
+    pub parts: Parts,
+}
+
+#[derive(Clap)]
+pub enum Parts {
+    Part01(Part01),
+    Part02(Part02),
+}
+
+/// Part 1
+#[derive(Clap)]
+pub struct Part01 {
+    /// Number of time steps to simulate
+    #[clap(short = "s", long = "steps", default_value = "1000")]
+    pub steps: usize,
+}
+
+/// Part 2
+#[derive(Clap)]
+pub struct Part02 {}
@@ -0,0 +1,26 @@
+use clap::Clap;
+
+/// Day 13: Care Package
+#[derive(Clap)]
+pub struct Day13 {
+    #[clap(subcommand)]
+    pub parts: Parts,
+}
+
+#[derive(Clap)]
+pub enum Parts {
+    Part01(Part01),
+    Part02(Part02),
+}
+
+/// Part 1
+#[derive(Clap)]
+pub struct Part01 {}
+
+/// Part 2
+#[derive(Clap)]
+pub struct Part02 {
+    /// Draw each frame to the terminal while the game plays
+    #[clap(short = "r", long = "render")]
+    pub render: bool,
+}
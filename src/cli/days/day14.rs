@@ -0,0 +1,26 @@
+use clap::Clap;
+
+/// Day 14: Space Stoichiometry
+#[derive(Clap)]
+pub struct Day14 {
+    #[clap(subcommand)]
+    pub parts: Parts,
+}
+
+#[derive(Clap)]
+pub enum Parts {
+    Part01(Part01),
+    Part02(Part02),
+}
+
+/// Part 1
+#[derive(Clap)]
+pub struct Part01 {}
+
+/// Part 2
+#[derive(Clap)]
+pub struct Part02 {
+    /// ORE available to produce FUEL from
+    #[clap(short = "o", long = "ore", default_value = "1000000000000")]
+    pub ore: u64,
+}
@@ -0,0 +1,30 @@
+use clap::Clap;
+
+/// Day 15: Oxygen System
+#[derive(Clap)]
+pub struct Day15 {
+    #[clap(subcommand)]
+    pub parts: Parts,
+}
+
+#[derive(Clap)]
+pub enum Parts {
+    Part01(Part01),
+    Part02(Part02),
+}
+
+/// Part 1
+#[derive(Clap)]
+pub struct Part01 {
+    /// Print the map discovered by the droid
+    #[clap(short = "m", long = "map")]
+    pub map: bool,
+}
+
+/// Part 2
+#[derive(Clap)]
+pub struct Part02 {
+    /// Print the map discovered by the droid
+    #[clap(short = "m", long = "map")]
+    pub map: bool,
+}
@@ -0,0 +1,28 @@
+use clap::Clap;
+
+/// Day 16: Flawed Frequency Transmission
+#[derive(Clap)]
+pub struct Day16 {
+    #[clap(subcommand)]
+    pub parts: Parts,
+}
+
+#[derive(Clap)]
+pub enum Parts {
+    Part01(Part01),
+    Part02(Part02),
+}
+
+/// Part 1
+#[derive(Clap)]
+pub struct Part01 {
+    #[clap(short = "p", long = "phases", default_value = "100")]
+    pub phases: usize,
+}
+
+/// Part 2
+#[derive(Clap)]
+pub struct Part02 {
+    #[clap(short = "p", long = "phases", default_value = "100")]
+    pub phases: usize,
+}
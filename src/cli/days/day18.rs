@@ -0,0 +1,22 @@
+use clap::Clap;
+
+/// Day 18: Many-Worlds Interpretation
+#[derive(Clap)]
+pub struct Day18 {
+    #[clap(subcommand)]
+    pub parts: Parts,
+}
+
+#[derive(Clap)]
+pub enum Parts {
+    Part01(Part01),
+    Part02(Part02),
+}
+
+/// Part 1
+#[derive(Clap)]
+pub struct Part01 {}
+
+/// Part 2
+#[derive(Clap)]
+pub struct Part02 {}
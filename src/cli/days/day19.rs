@@ -0,0 +1,30 @@
+use clap::Clap;
+
+/// Day 19: Tractor Beam
+#[derive(Clap)]
+pub struct Day19 {
+    #[clap(subcommand)]
+    pub parts: Parts,
+}
+
+#[derive(Clap)]
+pub enum Parts {
+    Part01(Part01),
+    Part02(Part02),
+}
+
+/// Part 1
+#[derive(Clap)]
+pub struct Part01 {
+    /// Size of the area scanned
+    #[clap(short = "s", long = "size", default_value = "50")]
+    pub size: usize,
+}
+
+/// Part 2
+#[derive(Clap)]
+pub struct Part02 {
+    /// Size of the square that must fit in the beam
+    #[clap(short = "s", long = "size", default_value = "100")]
+    pub size: usize,
+}
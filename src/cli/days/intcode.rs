@@ -0,0 +1,141 @@
+use clap::Clap;
+
+/// Intcode Tools
+#[derive(Clap)]
+pub struct Intcode {
+    #[clap(subcommand)]
+    pub tools: Tools,
+}
+
+#[derive(Clap)]
+pub enum Tools {
+    Ascii(Ascii),
+    Run(Run),
+    Image(Image),
+    Replay(Replay),
+    Coverage(Coverage),
+    Dap(Dap),
+    Compile(Compile),
+    Optimize(Optimize),
+}
+
+/// Run a program interactively using the terminal for ASCII input and output
+/// the program must be given with the input flag, as stdin is used for the terminal
+#[derive(Clap)]
+pub struct Ascii {}
+
+/// Run a program with scripted inputs, printing all outputs
+#[derive(Clap)]
+pub struct Run {
+    /// Comma separated list of inputs
+    #[clap(long = "inputs", use_delimiter = true)]
+    pub inputs: Vec<isize>,
+    /// File of comma or whitespace separated inputs, sent after any listed inputs
+    #[clap(long = "inputs-file")]
+    pub inputs_file: Option<String>,
+    /// Patch memory before running, as address=value
+    #[clap(long = "poke", parse(try_from_str = parse_poke), number_of_values = 1)]
+    pub pokes: Vec<(usize, isize)>,
+    /// Memory address to print once the program halts
+    #[clap(long = "peek", number_of_values = 1)]
+    pub peeks: Vec<usize>,
+    /// Print the final pointers and any unused inputs
+    #[clap(long = "state")]
+    pub state: bool,
+    /// Record the I/O session to a file for later replay
+    #[clap(long = "record")]
+    pub record: Option<String>,
+    /// Print a dump of memory once the program halts
+    #[clap(long = "dump-after")]
+    pub dump_after: bool,
+    /// Show the memory dump in hexadecimal alongside the cells read as ASCII
+    #[clap(long = "hex", requires = "dump-after")]
+    pub hex: bool,
+    /// Print the memory cells changed from the loaded program once it halts
+    #[clap(long = "diff-rom")]
+    pub diff_rom: bool,
+}
+
+/// Convert a program to a binary memory image
+#[derive(Clap)]
+pub struct Image {
+    /// File to write the memory image to
+    #[clap(short = "o", long = "output")]
+    pub output: String,
+}
+
+/// Replay a recorded session, stopping at the first divergence
+#[derive(Clap)]
+pub struct Replay {
+    /// Session file written by run --record
+    #[clap(short = "s", long = "session")]
+    pub session: String,
+}
+
+/// Run a program with scripted inputs and report which instructions, branches and memory were used
+#[derive(Clap)]
+pub struct Coverage {
+    /// Comma separated list of inputs
+    #[clap(long = "inputs", use_delimiter = true)]
+    pub inputs: Vec<isize>,
+    /// File of comma or whitespace separated inputs, sent after any listed inputs
+    #[clap(long = "inputs-file")]
+    pub inputs_file: Option<String>,
+    /// Print an annotated listing of the program after the summary
+    #[clap(long = "listing")]
+    pub listing: bool,
+}
+
+/// Debug a program from an editor using the Debug Adapter Protocol
+/// the protocol is spoken over stdin and stdout unless a port is given
+#[derive(Clap)]
+pub struct Dap {
+    /// Listen for a single connection on this local TCP port instead of using stdio
+    #[clap(short = "p", long = "port")]
+    pub port: Option<u16>,
+}
+
+/// Compile a program written in the Intcode language
+#[derive(Clap)]
+pub struct Compile {
+    /// File to write the compiled program to
+    #[clap(short = "o", long = "output")]
+    pub output: String,
+    /// Write comma separated text instead of a binary memory image
+    #[clap(long = "text")]
+    pub text: bool,
+}
+
+/// Optimize a program, verifying the result against recorded input sets
+#[derive(Clap)]
+pub struct Optimize {
+    /// File to write the optimized program to
+    #[clap(short = "o", long = "output")]
+    pub output: String,
+    /// Write comma separated text instead of a binary memory image
+    #[clap(long = "text")]
+    pub text: bool,
+    /// Session file written by run --record, its inputs are used to verify the optimized program
+    #[clap(short = "s", long = "session", number_of_values = 1)]
+    pub sessions: Vec<String>,
+    /// Comma separated list of inputs to verify the optimized program with
+    #[clap(long = "inputs", use_delimiter = true)]
+    pub inputs: Vec<isize>,
+}
+
+fn parse_poke(poke: &str) -> Result<(usize, isize), String> {
+    let mut split = poke.splitn(2, '=');
+    let address = split.next().unwrap_or_default();
+    let value = split
+        .next()
+        .ok_or_else(|| format!("Expected address=value but got {}", poke))?;
+
+    Ok((
+        address
+            .parse()
+            .map_err(|e| format!("Invalid address {}: {}", address, e))?,
+        value
+            .parse()
+            .map_err(|e| format!("Invalid value {}: {}", value, e))?,
+    ))
+}
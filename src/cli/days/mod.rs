@@ -11,6 +11,17 @@ mod day06;
 mod day07;
 mod day08;
 mod day09;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod intcode;
 mod shared;
 
 /// List of Days
@@ -25,6 +36,24 @@ pub enum Days {
     Day07(day07::Day07),
     Day08(day08::Day08),
     Day09(day09::Day09),
+    Day10(day10::Day10),
+    Day11(day11::Day11),
+    Day12(day12::Day12),
+    Day13(day13::Day13),
+    Day14(day14::Day14),
+    Day15(day15::Day15),
+    Day16(day16::Day16),
+    Day17(day17::Day17),
+    Day18(day18::Day18),
+    Day19(day19::Day19),
+    Intcode(intcode::Intcode),
+}
+
+impl Days {
+    /// Whether the command takes input from stdin while it runs, so cannot also read its puzzle input from it
+    pub fn interactive(&self) -> bool {
+        matches!(self, Days::Intcode(i) if matches!(i.tools, intcode::Tools::Ascii(_)))
+    }
 }
 
 pub fn run<T>(reader: T, day: Days) -> Result<String, Box<dyn error::Error>>
@@ -69,5 +98,74 @@ where
             shared::Parts::Part01(_) => crate::days::day09::part01(reader),
             shared::Parts::Part02(_) => crate::days::day09::part02(reader),
         },
+        Days::Day10(d) => match d.parts {
+            day10::Parts::Part01(_) => crate::days::day10::part01(reader),
+            day10::Parts::Part02(p) => crate::days::day10::part02(reader, p.nth),
+        },
+        Days::Day11(d) => match d.parts {
+            day11::Parts::Part01(p) => crate::days::day11::part01(reader, p.start),
+            day11::Parts::Part02(p) => crate::days::day11::part02(reader, p.start),
+        },
+        Days::Day12(d) => match d.parts {
+            day12::Parts::Part01(p) => crate::days::day12::part01(reader, p.steps),
+            day12::Parts::Part02(_) => crate::days::day12::part02(reader),
+        },
+        Days::Day13(d) => match d.parts {
+            day13::Parts::Part01(_) => crate::days::day13::part01(reader),
+            day13::Parts::Part02(p) => crate::days::day13::part02(reader, p.render),
+        },
+        Days::Day14(d) => match d.parts {
+            day14::Parts::Part01(_) => crate::days::day14::part01(reader),
+            day14::Parts::Part02(p) => crate::days::day14::part02(reader, p.ore),
+        },
+        Days::Day15(d) => match d.parts {
+            day15::Parts::Part01(p) => crate::days::day15::part01(reader, p.map),
+            day15::Parts::Part02(p) => crate::days::day15::part02(reader, p.map),
+        },
+        Days::Day16(d) => match d.parts {
+            day16::Parts::Part01(p) => crate::days::day16::part01(reader, p.phases),
+            day16::Parts::Part02(p) => crate::days::day16::part02(reader, p.phases),
+        },
+        Days::Day17(d) => match d.parts {
+            day17::Parts::Part01(_) => crate::days::day17::part01(reader),
+            day17::Parts::Part02(_) => crate::days::day17::part02(reader),
+        },
+        Days::Day18(d) => match d.parts {
+            day18::Parts::Part01(_) => crate::days::day18::part01(reader),
+            day18::Parts::Part02(_) => crate::days::day18::part02(reader),
+        },
+        Days::Day19(d) => match d.parts {
+            day19::Parts::Part01(p) => crate::days::day19::part01(reader, p.size),
+            day19::Parts::Part02(p) => crate::days::day19::part02(reader, p.size),
+        },
+        Days::Intcode(i) => match i.tools {
+            intcode::Tools::Ascii(_) => {
+                crate::tools::intcode::ascii(reader, io::stdin().lock(), io::stdout())
+            }
+            intcode::Tools::Run(r) => crate::tools::intcode::run(
+                reader,
+                crate::tools::intcode::RunOptions {
+                    inputs: r.inputs,
+                    inputs_file: r.inputs_file,
+                    pokes: r.pokes,
+                    peeks: r.peeks,
+                    state: r.state,
+                    record: r.record,
+                    dump_after: r.dump_after,
+                    hex: r.hex,
+                    diff_rom: r.diff_rom,
+                },
+            ),
+            intcode::Tools::Image(i) => crate::tools::intcode::image(reader, i.output),
+            intcode::Tools::Replay(r) => crate::tools::intcode::replay(reader, r.session),
+            intcode::Tools::Coverage(c) => {
+                crate::tools::intcode::coverage(reader, c.inputs, c.inputs_file, c.listing)
+            }
+            intcode::Tools::Dap(d) => crate::tools::intcode::dap(reader, d.port),
+            intcode::Tools::Compile(c) => crate::tools::intcode::compile(reader, c.output, c.text),
+            intcode::Tools::Optimize(o) => {
+                crate::tools::intcode::optimize(reader, o.output, o.text, o.sessions, o.inputs)
+            }
+        },
     }
 }
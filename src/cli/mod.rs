@@ -27,9 +27,12 @@ pub fn run() -> Result<String, Box<dyn error::Error>> {
             days::run(reader, opts.subcmd)
         }
         None => {
+            if opts.subcmd.interactive() {
+                return Err(Box::new(io::Error::other(
+                    "This command reads from stdin as it runs! Please provide a file using the input flag")));
+            }
             if atty::is(atty::Stream::Stdin) {
-                return Err(Box::new(io::Error::new(
-                    io::ErrorKind::Other,
+                return Err(Box::new(io::Error::other(
                     "No Input stream found! Please provide a file by piping or using the input flag")));
             }
             let reader = io::BufReader::new(io::stdin());
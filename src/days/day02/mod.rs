@@ -14,10 +14,10 @@ where
 
     match computer.run()? {
         intcode::HaltedState::Halt => Ok(format!("Day 02 Part 01 Answer: {}", computer.ram[&0])),
-        state => Err(Box::new(io::Error::new(
-            io::ErrorKind::Other,
-            format!("Unexpected Halted State: {:?}", state),
-        ))),
+        state => Err(Box::new(io::Error::other(format!(
+            "Unexpected Halted State: {:?}",
+            state
+        )))),
     }
 }
 
@@ -40,18 +40,18 @@ where
                     };
                 }
                 state => {
-                    return Err(Box::new(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("Unexpected Halted State: {:?}", state),
-                    )))
+                    return Err(Box::new(io::Error::other(format!(
+                        "Unexpected Halted State: {:?}",
+                        state
+                    ))))
                 }
             }
 
             comp.reset();
         }
     }
-    Err(Box::new(io::Error::new(
-        io::ErrorKind::Other,
-        format!("Unable to find a noun and verb to get target of {}", target),
-    )))
+    Err(Box::new(io::Error::other(format!(
+        "Unable to find a noun and verb to get target of {}",
+        target
+    ))))
 }
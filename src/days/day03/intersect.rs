@@ -12,7 +12,7 @@ pub struct Wire {
 
 impl<'a> Wire {
     pub fn closet_intersection(&self, other_wire: &Self) -> Option<u32> {
-        let (smaller, larger) = self.order(&other_wire);
+        let (smaller, larger) = self.order(other_wire);
 
         let mut closet = None;
 
@@ -31,7 +31,7 @@ impl<'a> Wire {
     }
 
     pub fn fewest_steps(&self, other_wire: &Self) -> Option<u32> {
-        let (smaller, larger) = self.order(&other_wire);
+        let (smaller, larger) = self.order(other_wire);
 
         let mut fewest = None;
 
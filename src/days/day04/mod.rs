@@ -16,8 +16,7 @@ where
     let start = match split.next() {
         Some(value) => value.parse::<u32>()?,
         _ => {
-            return Err(Box::new(io::Error::new(
-                io::ErrorKind::Other,
+            return Err(Box::new(io::Error::other(
                 "No Value Found in Start Range (before hypen)",
             )))
         }
@@ -26,8 +25,7 @@ where
     let end = match split.next() {
         Some(value) => value.parse::<u32>()?,
         _ => {
-            return Err(Box::new(io::Error::new(
-                io::ErrorKind::Other,
+            return Err(Box::new(io::Error::other(
                 "No Value Found in End Range (after hypen)",
             )))
         }
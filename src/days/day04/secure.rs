@@ -86,10 +86,8 @@ impl PatternCounter {
         let mut count = 0;
         let mut number = self.min_number.clone();
         loop {
-            if Self::is_increasing(&number) {
-                if check_2(&number) {
-                    count += 1;
-                }
+            if Self::is_increasing(&number) && check_2(&number) {
+                count += 1;
             }
 
             if number >= self.max_number {
@@ -120,6 +118,7 @@ impl PatternCounter {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test {
     use super::*;
 
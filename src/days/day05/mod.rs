@@ -1,5 +1,6 @@
 use std::error;
 use std::io;
+use std::iter;
 
 use crate::shared::intcode;
 
@@ -10,20 +11,12 @@ where
     let mem = intcode::parse_mem(reader)?;
     let mut computer = intcode::Computer::new(&mem);
 
-    let mut last_output = None;
-    loop {
-        match computer.run()? {
-            intcode::HaltedState::Halt => {
-                return Ok(format!(
-                    "Day 05 Part 01 Answer: {}",
-                    last_output
-                        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "No Output!"))?
-                ))
-            }
-            intcode::HaltedState::Input => computer.send_input(input),
-            intcode::HaltedState::Output(output) => last_output = Some(output),
-        }
-    }
+    let last_output = computer
+        .outputs(iter::repeat(input))
+        .last()
+        .ok_or_else(|| io::Error::other("No Output!"))??;
+
+    Ok(format!("Day 05 Part 01 Answer: {}", last_output))
 }
 
 pub fn part02<T>(reader: T, input: isize) -> Result<String, Box<dyn error::Error>>
@@ -33,18 +26,10 @@ where
     let mem = intcode::parse_mem(reader)?;
     let mut computer = intcode::Computer::new(&mem);
 
-    let mut last_output = None;
-    loop {
-        match computer.run()? {
-            intcode::HaltedState::Halt => {
-                return Ok(format!(
-                    "Day 05 Part 01 Answer: {}",
-                    last_output
-                        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "No Output!"))?
-                ))
-            }
-            intcode::HaltedState::Input => computer.send_input(input),
-            intcode::HaltedState::Output(output) => last_output = Some(output),
-        }
-    }
+    let last_output = computer
+        .outputs(iter::repeat(input))
+        .last()
+        .ok_or_else(|| io::Error::other("No Output!"))??;
+
+    Ok(format!("Day 05 Part 01 Answer: {}", last_output))
 }
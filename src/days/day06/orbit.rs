@@ -35,7 +35,7 @@ impl Orbits {
     pub fn total_orbits(&self) -> usize {
         let mut total = 0;
         for mass in self.masses.values() {
-            total += self.orbit_count(&mass);
+            total += self.orbit_count(mass);
         }
         total
     }
@@ -54,10 +54,10 @@ impl Orbits {
         let count = self.ids.len();
         let orbiter_id = *self.ids.entry(orbiter).or_insert(count);
 
-        let orbit_mass = self.masses.entry(orbit_id).or_insert_with(|| Mass::new());
+        let orbit_mass = self.masses.entry(orbit_id).or_insert_with(Mass::new);
         orbit_mass.orbiters.push(orbiter_id);
 
-        let orbiter_mass = self.masses.entry(orbiter_id).or_insert_with(|| Mass::new());
+        let orbiter_mass = self.masses.entry(orbiter_id).or_insert_with(Mass::new);
         orbiter_mass.orbit = Some(orbit_id);
     }
 
@@ -121,8 +121,8 @@ where
 
         let mut masses = line.split(")");
 
-        let orbit = masses.next().ok_or_else(|| "Unable to parse orbit")?;
-        let orbiter = masses.next().ok_or_else(|| "Unable to parse orbiter")?;
+        let orbit = masses.next().ok_or("Unable to parse orbit")?;
+        let orbiter = masses.next().ok_or("Unable to parse orbiter")?;
 
         orbits.insert(orbit.to_owned(), orbiter.to_owned());
     }
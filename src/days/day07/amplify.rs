@@ -15,7 +15,7 @@ impl<'a> Amps<'a> {
     ) -> Result<Self, Box<dyn error::Error>> {
         let mut amps = Vec::new();
         for _ in 0..phases.len() {
-            amps.push(intcode::Computer::new(&mem));
+            amps.push(intcode::Computer::new(mem));
         }
 
         Ok(Self { amps, phases })
@@ -33,7 +33,7 @@ impl<'a> Amps<'a> {
                 self.amps[index].reset();
                 match self.amps[index].run()? {
                     intcode::HaltedState::Input => {
-                        self.amps[index].send_input(**phase_setting);
+                        self.amps[index].send_input(**phase_setting)?;
                     }
                     _ => {
                         return Err(format!(
@@ -54,7 +54,7 @@ impl<'a> Amps<'a> {
                             break;
                         }
                         intcode::HaltedState::Input => {
-                            amp.send_input(last_output);
+                            amp.send_input(last_output)?;
                             match amp.run()? {
                                 intcode::HaltedState::Output(output) => {
                                     last_output = output;
@@ -13,7 +13,7 @@ impl Pixels {
     fn new(c: char) -> Result<Self, String> {
         let d = c
             .to_digit(10)
-            .ok_or_else(|| "Unable to convert character to a digit!")?;
+            .ok_or("Unable to convert character to a digit!")?;
 
         match d {
             0 => Ok(Self::Black),
@@ -51,11 +51,11 @@ pub struct Image<'a> {
 }
 
 impl<'a> Image<'a> {
-    pub fn new(pixels: &'a Vec<Pixels>, wide: usize, tall: usize) -> Result<Self, String> {
+    pub fn new(pixels: &'a [Pixels], wide: usize, tall: usize) -> Result<Self, String> {
         let mut layers = Vec::new();
         let pixels_per_layer = wide * tall;
 
-        if pixels.len() % pixels_per_layer != 0 {
+        if !pixels.len().is_multiple_of(pixels_per_layer) {
             return Err("There are some pixels remaining, unable to generate image".to_string());
         }
 
@@ -92,14 +92,8 @@ impl<'a> Image<'a> {
         }
         match fewest_counter {
             Some(counter) => {
-                let white_count = match counter.get(&Pixels::White) {
-                    Some(count) => count,
-                    None => &0,
-                };
-                let transparent_count = match counter.get(&Pixels::Transparent) {
-                    Some(count) => count,
-                    None => &0,
-                };
+                let white_count = counter.get(&Pixels::White).unwrap_or(&0);
+                let transparent_count = counter.get(&Pixels::Transparent).unwrap_or(&0);
 
                 white_count * transparent_count
             }
@@ -130,7 +124,7 @@ impl<'a> Image<'a> {
 
         for pixel_index in 0..self.pixels_per_layer {
             if pixel_index % self.wide == 0 {
-                display.push_str("\n");
+                display.push('\n');
             }
             display.push_str(match self.flatten_pixel(0, pixel_index) {
                 Pixels::Black | Pixels::Transparent => " ",
@@ -154,9 +148,9 @@ where
     reader
         .lines()
         .next()
-        .ok_or_else(|| "Unable to parse! Empty File?")?
-        .or_else(|x| Err(format!("Error Parsing File: {}", x)))?
+        .ok_or("Unable to parse! Empty File?")?
+        .map_err(|x| format!("Error Parsing File: {}", x))?
         .chars()
-        .map(|c| Pixels::new(c))
+        .map(Pixels::new)
         .collect::<Result<Vec<Pixels>, String>>()
 }
@@ -1,5 +1,6 @@
 use std::error;
 use std::io;
+use std::iter;
 
 use crate::shared::intcode;
 
@@ -10,14 +11,7 @@ where
     let mem = intcode::parse_mem(reader)?;
     let mut computer = intcode::Computer::new(&mem);
 
-    let mut last_output = 0;
-    loop {
-        match computer.run()? {
-            intcode::HaltedState::Halt => break,
-            intcode::HaltedState::Input => computer.send_input(1),
-            intcode::HaltedState::Output(output) => last_output = output,
-        }
-    }
+    let last_output = computer.outputs(iter::repeat(1)).last().unwrap_or(Ok(0))?;
 
     Ok(format!("Day 09 Part 01 Answer: {}", last_output))
 }
@@ -29,14 +23,7 @@ where
     let mem = intcode::parse_mem(reader)?;
     let mut computer = intcode::Computer::new(&mem);
 
-    let mut last_output = 0;
-    loop {
-        match computer.run()? {
-            intcode::HaltedState::Halt => break,
-            intcode::HaltedState::Input => computer.send_input(1),
-            intcode::HaltedState::Output(output) => last_output = output,
-        }
-    }
+    let last_output = computer.outputs(iter::repeat(1)).last().unwrap_or(Ok(0))?;
 
     Ok(format!("Day 09 Part 01 Answer: {}", last_output))
 }
@@ -0,0 +1,36 @@
+use std::error;
+use std::io;
+
+mod monitoring;
+
+pub fn part01<T>(reader: T) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let field = monitoring::parse_field(reader)?;
+
+    let (_, detected) = field.best_station().ok_or("No asteroids found!")?;
+    Ok(format!("Day 10 Part 01 Answer: {}", detected))
+}
+
+pub fn part02<T>(reader: T, nth: usize) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let field = monitoring::parse_field(reader)?;
+
+    let (station, _) = field.best_station().ok_or("No asteroids found!")?;
+    let order = field.vaporization_order(station);
+    let (x, y) = nth
+        .checked_sub(1)
+        .and_then(|index| order.get(index))
+        .ok_or_else(|| {
+            format!(
+                "Unable to vaporize asteroid #{}, only {} asteroids can be vaporized",
+                nth,
+                order.len()
+            )
+        })?;
+
+    Ok(format!("Day 10 Part 02 Answer: {}", x * 100 + y))
+}
@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::error;
+use std::io;
+
+/// Asteroid location, x grows to the right and y grows downwards
+pub type Point = (isize, isize);
+
+fn gcd(a: isize, b: isize) -> isize {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Direction from one point to another reduced to lowest terms, along with the number of steps
+/// any points along the same line of sight share the same direction
+fn direction(from: Point, to: Point) -> (Point, isize) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let steps = gcd(dx, dy);
+    ((dx / steps, dy / steps), steps)
+}
+
+/// Order directions clockwise starting from straight up, using exact integer arithmetic
+/// directions are split into the right half (including up) and the left half (including down),
+/// then ordered within a half by the sign of their cross product
+fn clockwise(a: &Point, b: &Point) -> Ordering {
+    let half = |(x, y): &Point| !(*x > 0 || (*x == 0 && *y < 0));
+    half(a)
+        .cmp(&half(b))
+        .then_with(|| (b.0 * a.1).cmp(&(a.0 * b.1)))
+}
+
+pub struct AsteroidField {
+    asteroids: Vec<Point>,
+}
+
+impl AsteroidField {
+    /// Number of asteroids visible from a point
+    fn detected(&self, station: Point) -> usize {
+        self.asteroids
+            .iter()
+            .filter(|asteroid| **asteroid != station)
+            .map(|asteroid| direction(station, *asteroid).0)
+            .collect::<HashSet<Point>>()
+            .len()
+    }
+
+    /// Asteroid able to detect the most other asteroids, along with how many it detects
+    pub fn best_station(&self) -> Option<(Point, usize)> {
+        self.asteroids
+            .iter()
+            .map(|asteroid| (*asteroid, self.detected(*asteroid)))
+            .max_by_key(|(_, detected)| *detected)
+    }
+
+    /// Order asteroids are vaporized in by a laser at the station
+    /// rotating clockwise from straight up, hitting the closest asteroid in each direction per rotation
+    pub fn vaporization_order(&self, station: Point) -> Vec<Point> {
+        let mut lines: HashMap<Point, Vec<(isize, Point)>> = HashMap::new();
+        for asteroid in self.asteroids.iter().filter(|a| **a != station) {
+            let (direction, steps) = direction(station, *asteroid);
+            lines.entry(direction).or_default().push((steps, *asteroid));
+        }
+
+        let mut order = Vec::with_capacity(self.asteroids.len());
+        for (direction, mut line) in lines {
+            line.sort();
+            for (rotation, (_, asteroid)) in line.into_iter().enumerate() {
+                order.push((rotation, direction, asteroid));
+            }
+        }
+        order.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| clockwise(&a.1, &b.1)));
+
+        order.into_iter().map(|(_, _, asteroid)| asteroid).collect()
+    }
+}
+
+/// Parse a map of asteroids, `#` is an asteroid and `.` is empty space
+pub fn parse_field<T>(reader: T) -> Result<AsteroidField, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mut asteroids = Vec::new();
+
+    for (y, res) in reader.lines().enumerate() {
+        let line = res?;
+        for (x, c) in line.trim_end().chars().enumerate() {
+            match c {
+                '#' => asteroids.push((x as isize, y as isize)),
+                '.' => (),
+                c => return Err(format!("Unexpected character in map: {}", c).into()),
+            }
+        }
+    }
+    Ok(AsteroidField { asteroids })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_best_station() {
+        let input = ".#..#
+.....
+#####
+....#
+...##"
+            .as_bytes();
+
+        let field = parse_field(input).unwrap();
+        assert_eq!(field.best_station(), Some(((3, 4), 8)));
+
+        let input = "......#.#.
+#..#.#....
+..#######.
+.#.#.###..
+.#..#.....
+..#....#.#
+#..#....#.
+.##.#..###
+##...#..#.
+.#....####"
+            .as_bytes();
+
+        let field = parse_field(input).unwrap();
+        assert_eq!(field.best_station(), Some(((5, 8), 33)));
+    }
+
+    #[test]
+    fn test_vaporization_order() {
+        let input = ".#....#####...#..
+##...##.#####..##
+##...#...#.#####.
+..#.....#...###..
+..#.#.....#....##"
+            .as_bytes();
+
+        let field = parse_field(input).unwrap();
+        let order = field.vaporization_order((8, 3));
+
+        assert_eq!(
+            order[..9],
+            [
+                (8, 1),
+                (9, 0),
+                (9, 1),
+                (10, 0),
+                (9, 2),
+                (11, 1),
+                (12, 1),
+                (11, 2),
+                (15, 1)
+            ]
+        );
+        assert_eq!(order.len(), 36);
+    }
+}
@@ -0,0 +1,28 @@
+use std::error;
+use std::io;
+
+use crate::shared::intcode;
+
+mod robot;
+
+pub fn part01<T>(reader: T, start: String) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let mut robot = robot::Robot::new(intcode::Computer::new(&mem), start.parse()?);
+    robot.run()?;
+
+    Ok(format!("Day 11 Part 01 Answer: {}", robot.painted()))
+}
+
+pub fn part02<T>(reader: T, start: String) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let mut robot = robot::Robot::new(intcode::Computer::new(&mem), start.parse()?);
+    robot.run()?;
+
+    Ok(format!("Day 11 Part 02 Answer: {}", robot.render()))
+}
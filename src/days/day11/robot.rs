@@ -0,0 +1,188 @@
+use std::collections::{HashMap, HashSet};
+use std::error;
+use std::str::FromStr;
+
+use crate::shared::intcode;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    Black,
+    White,
+}
+
+impl Color {
+    fn new(value: isize) -> Result<Self, String> {
+        match value {
+            0 => Ok(Self::Black),
+            1 => Ok(Self::White),
+            x => Err(format!("Unexpected Paint Color: {}", x)),
+        }
+    }
+
+    fn value(self) -> isize {
+        match self {
+            Self::Black => 0,
+            Self::White => 1,
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "black" => Ok(Self::Black),
+            "white" => Ok(Self::White),
+            _ => Err(format!("Unknown Color {}, expected black or white", s)),
+        }
+    }
+}
+
+/// Location on the hull, x grows to the right and y grows downwards
+type Point = (isize, isize);
+
+/// Hull painting robot, the Computer decides what to paint and where to move
+pub struct Robot<'a> {
+    computer: intcode::Computer<'a>,
+    position: Point,
+    facing: Point,
+    hull: HashMap<Point, Color>,
+    painted: HashSet<Point>,
+}
+
+impl<'a> Robot<'a> {
+    /// Robot facing up, standing on a panel of the start color
+    /// every other panel starts black
+    pub fn new(computer: intcode::Computer<'a>, start: Color) -> Self {
+        let mut hull = HashMap::new();
+        hull.insert((0, 0), start);
+        Self {
+            computer,
+            position: (0, 0),
+            facing: (0, -1),
+            hull,
+            painted: HashSet::new(),
+        }
+    }
+
+    fn color(&self, point: &Point) -> Color {
+        *self.hull.get(point).unwrap_or(&Color::Black)
+    }
+
+    /// Paint the current panel, turn and move forward one panel
+    fn paint(&mut self, color: isize, turn: isize) -> Result<(), String> {
+        self.hull.insert(self.position, Color::new(color)?);
+        self.painted.insert(self.position);
+
+        let (x, y) = self.facing;
+        self.facing = match turn {
+            0 => (y, -x),
+            1 => (-y, x),
+            x => return Err(format!("Unexpected Turn Direction: {}", x)),
+        };
+        self.position = (
+            self.position.0 + self.facing.0,
+            self.position.1 + self.facing.1,
+        );
+        Ok(())
+    }
+
+    /// Run until the program halts
+    /// the camera reports the current panel color and each pair of outputs is a paint color and turn
+    pub fn run(&mut self) -> Result<(), Box<dyn error::Error>> {
+        let mut outputs = Vec::with_capacity(2);
+        loop {
+            match self.computer.run()? {
+                intcode::HaltedState::Halt => break,
+                intcode::HaltedState::Input => {
+                    let color = self.color(&self.position);
+                    self.computer.send_input(color.value())?;
+                }
+                intcode::HaltedState::Output(output) => {
+                    outputs.push(output);
+                    if outputs.len() == 2 {
+                        self.paint(outputs[0], outputs[1])?;
+                        outputs.clear();
+                    }
+                }
+            }
+        }
+        if !outputs.is_empty() {
+            return Err("Program halted after a paint color without a turn direction".into());
+        }
+        Ok(())
+    }
+
+    /// Number of panels painted at least once
+    pub fn painted(&self) -> usize {
+        self.painted.len()
+    }
+
+    /// Renders the white panels to a visible string "image"
+    pub fn render(&self) -> String {
+        let white: Vec<&Point> = self
+            .hull
+            .iter()
+            .filter(|(_, color)| **color == Color::White)
+            .map(|(point, _)| point)
+            .collect();
+
+        let min_x = white.iter().map(|(x, _)| *x).min().unwrap_or(0);
+        let max_x = white.iter().map(|(x, _)| *x).max().unwrap_or(-1);
+        let min_y = white.iter().map(|(_, y)| *y).min().unwrap_or(0);
+        let max_y = white.iter().map(|(_, y)| *y).max().unwrap_or(-1);
+
+        let mut display = String::new();
+        for y in min_y..=max_y {
+            display.push('\n');
+            for x in min_x..=max_x {
+                display.push(match self.color(&(x, y)) {
+                    Color::Black => ' ',
+                    Color::White => '*',
+                });
+            }
+        }
+        display
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Program reading a panel color before each pair of outputs, then halting
+    fn scripted(pairs: &[(isize, isize)]) -> HashMap<usize, isize> {
+        let mut program = String::new();
+        for (color, turn) in pairs {
+            program.push_str(&format!("3,1000,104,{},104,{},", color, turn));
+        }
+        program.push_str("99");
+        intcode::parse_mem(program.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_painted() {
+        let mem = scripted(&[(1, 0), (0, 0), (1, 0), (1, 0), (0, 1), (1, 0), (1, 0)]);
+        let mut robot = Robot::new(intcode::Computer::new(&mem), Color::Black);
+        robot.run().unwrap();
+
+        assert_eq!(robot.painted(), 6);
+        assert_eq!(robot.position, (0, -1));
+        assert_eq!(robot.facing, (-1, 0));
+        assert_eq!(robot.render(), "\n  *\n  *\n** ");
+    }
+
+    #[test]
+    fn test_start_color() {
+        // paints the starting panel black, then paints an L of three white panels
+        let mem = scripted(&[(0, 1), (1, 0), (1, 1), (1, 1)]);
+        let mut robot = Robot::new(intcode::Computer::new(&mem), Color::White);
+        robot.run().unwrap();
+
+        assert_eq!(robot.painted(), 4);
+        assert_eq!(robot.render(), "\n**\n* ");
+        assert_eq!("White".parse::<Color>().unwrap(), Color::White);
+        assert!("red".parse::<Color>().is_err());
+    }
+}
@@ -0,0 +1,26 @@
+use std::error;
+use std::io;
+
+mod moons;
+
+pub fn part01<T>(reader: T, steps: usize) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mut system = moons::parse_moons(reader)?;
+    for _ in 0..steps {
+        system.step();
+    }
+
+    Ok(format!("Day 12 Part 01 Answer: {}", system.energy()))
+}
+
+pub fn part02<T>(reader: T) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let system = moons::parse_moons(reader)?;
+    let period = system.period()?;
+
+    Ok(format!("Day 12 Part 02 Answer: {}", period))
+}
@@ -0,0 +1,203 @@
+use std::error;
+use std::io;
+
+/// Number of axes moons move along
+const AXES: usize = 3;
+
+#[derive(Clone, PartialEq)]
+struct Moon {
+    position: [isize; AXES],
+    velocity: [isize; AXES],
+}
+
+impl Moon {
+    fn energy(&self) -> isize {
+        let potential: isize = self.position.iter().map(|p| p.abs()).sum();
+        let kinetic: isize = self.velocity.iter().map(|v| v.abs()).sum();
+        potential * kinetic
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Least common multiple, failing rather than overflowing
+fn lcm(a: u64, b: u64) -> Result<u64, String> {
+    (a / gcd(a, b))
+        .checked_mul(b)
+        .ok_or_else(|| format!("Least common multiple of {} and {} is too large", a, b))
+}
+
+pub struct System {
+    moons: Vec<Moon>,
+}
+
+impl System {
+    /// Apply gravity between every pair of moons along a single axis, then velocity
+    /// axes never affect each other so each can be simulated on its own
+    fn step_axis(&mut self, axis: usize) {
+        for first in 0..self.moons.len() {
+            for second in first + 1..self.moons.len() {
+                let pull =
+                    (self.moons[second].position[axis] - self.moons[first].position[axis]).signum();
+                self.moons[first].velocity[axis] += pull;
+                self.moons[second].velocity[axis] -= pull;
+            }
+        }
+        for moon in &mut self.moons {
+            moon.position[axis] += moon.velocity[axis];
+        }
+    }
+
+    /// Simulate one time step
+    pub fn step(&mut self) {
+        for axis in 0..AXES {
+            self.step_axis(axis);
+        }
+    }
+
+    /// Total energy of all moons
+    pub fn energy(&self) -> isize {
+        self.moons.iter().map(Moon::energy).sum()
+    }
+
+    /// Steps until an axis first returns to its starting state
+    /// each step can be reversed, so the first repeated state is always the starting state
+    fn axis_period(&self, axis: usize) -> u64 {
+        let state = |system: &Self| -> Vec<(isize, isize)> {
+            system
+                .moons
+                .iter()
+                .map(|moon| (moon.position[axis], moon.velocity[axis]))
+                .collect()
+        };
+
+        let start = state(self);
+        let mut system = Self {
+            moons: self.moons.clone(),
+        };
+        let mut steps = 0;
+        loop {
+            system.step_axis(axis);
+            steps += 1;
+            if state(&system) == start {
+                return steps;
+            }
+        }
+    }
+
+    /// Steps until the whole system returns to a previous state
+    /// found by combining the period of each independent axis
+    pub fn period(&self) -> Result<u64, String> {
+        (0..AXES).try_fold(1, |period, axis| lcm(period, self.axis_period(axis)))
+    }
+}
+
+/// Parse a single value, as name=value
+fn parse_value(token: &str, name: char) -> Result<isize, String> {
+    let token = token.trim();
+    token
+        .strip_prefix(name)
+        .and_then(|token| token.strip_prefix('='))
+        .ok_or_else(|| format!("Expected {}=<value> but found {}", name, token))?
+        .parse()
+        .map_err(|e| format!("Unable to parse value {}: {}", token, e))
+}
+
+/// Parse moon positions, one per line as <x=1, y=2, z=3>
+pub fn parse_moons<T>(reader: T) -> Result<System, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mut moons = Vec::new();
+
+    for res in reader.lines() {
+        let line = res?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let values = line
+            .strip_prefix('<')
+            .and_then(|line| line.strip_suffix('>'))
+            .ok_or_else(|| format!("Unable to parse moon {}", line))?;
+
+        let mut tokens = values.split(',');
+        let mut position = [0; AXES];
+        for (value, name) in position.iter_mut().zip(['x', 'y', 'z'].iter()) {
+            let token = tokens
+                .next()
+                .ok_or_else(|| format!("Missing {} for moon {}", name, line))?;
+            *value = parse_value(token, *name)?;
+        }
+        if tokens.next().is_some() {
+            return Err(format!("Unexpected values for moon {}", line).into());
+        }
+
+        moons.push(Moon {
+            position,
+            velocity: [0; AXES],
+        });
+    }
+    Ok(System { moons })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FIRST_EXAMPLE: &str = "<x=-1, y=0, z=2>
+<x=2, y=-10, z=-7>
+<x=4, y=-8, z=8>
+<x=3, y=5, z=-1>";
+
+    const SECOND_EXAMPLE: &str = "<x=-8, y=-10, z=0>
+<x=5, y=5, z=10>
+<x=2, y=-7, z=3>
+<x=9, y=-8, z=-3>";
+
+    #[test]
+    fn test_energy() {
+        let mut system = parse_moons(FIRST_EXAMPLE.as_bytes()).unwrap();
+        for _ in 0..10 {
+            system.step();
+        }
+        assert_eq!(system.moons[0].position, [2, 1, -3]);
+        assert_eq!(system.moons[0].velocity, [-3, -2, 1]);
+        assert_eq!(system.energy(), 179);
+
+        let mut system = parse_moons(SECOND_EXAMPLE.as_bytes()).unwrap();
+        for _ in 0..100 {
+            system.step();
+        }
+        assert_eq!(system.energy(), 1940);
+    }
+
+    #[test]
+    fn test_period() {
+        let system = parse_moons(FIRST_EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(system.period().unwrap(), 2772);
+
+        let system = parse_moons(SECOND_EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(system.period().unwrap(), 4_686_774_924);
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(lcm(4, 6).unwrap(), 12);
+        assert!(lcm(u64::MAX, u64::MAX - 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(parse_moons("<x=1, y=2>".as_bytes()).is_err());
+        assert!(parse_moons("<x=1, y=2, w=3>".as_bytes()).is_err());
+        assert!(parse_moons("x=1, y=2, z=3".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::error;
+use std::io;
+
+use crate::shared::intcode;
+
+/// Escape sequence clearing the terminal and moving the cursor to the top left
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tile {
+    Empty,
+    Wall,
+    Block,
+    Paddle,
+    Ball,
+}
+
+impl Tile {
+    fn new(id: isize) -> Result<Self, String> {
+        match id {
+            0 => Ok(Self::Empty),
+            1 => Ok(Self::Wall),
+            2 => Ok(Self::Block),
+            3 => Ok(Self::Paddle),
+            4 => Ok(Self::Ball),
+            x => Err(format!("Unexpected Tile Id: {}", x)),
+        }
+    }
+
+    fn render(self) -> char {
+        match self {
+            Self::Empty => ' ',
+            Self::Wall => '#',
+            Self::Block => '=',
+            Self::Paddle => '-',
+            Self::Ball => 'o',
+        }
+    }
+}
+
+/// Arcade cabinet, the Computer draws tiles and reads the joystick
+pub struct Arcade<'a> {
+    computer: intcode::Computer<'a>,
+    screen: HashMap<(isize, isize), Tile>,
+    score: isize,
+    ball: isize,
+    paddle: isize,
+}
+
+impl<'a> Arcade<'a> {
+    pub fn new(computer: intcode::Computer<'a>) -> Self {
+        Self {
+            computer,
+            screen: HashMap::new(),
+            score: 0,
+            ball: 0,
+            paddle: 0,
+        }
+    }
+
+    /// Draw a tile, or update the score when drawing to x=-1, y=0
+    fn draw(&mut self, x: isize, y: isize, id: isize) -> Result<(), String> {
+        if (x, y) == (-1, 0) {
+            self.score = id;
+            return Ok(());
+        }
+
+        let tile = Tile::new(id)?;
+        match tile {
+            Tile::Ball => self.ball = x,
+            Tile::Paddle => self.paddle = x,
+            _ => (),
+        }
+        self.screen.insert((x, y), tile);
+        Ok(())
+    }
+
+    /// Run until the game halts, moving the paddle towards the ball whenever the joystick is read
+    /// each frame is written to render before the joystick is read
+    pub fn play(
+        &mut self,
+        mut render: Option<&mut dyn io::Write>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let mut outputs = Vec::with_capacity(3);
+        loop {
+            match self.computer.run()? {
+                intcode::HaltedState::Halt => break,
+                intcode::HaltedState::Input => {
+                    if let Some(writer) = &mut render {
+                        write!(writer, "{}{}", CLEAR_SCREEN, self.frame())?;
+                        writer.flush()?;
+                    }
+                    self.computer
+                        .send_input((self.ball - self.paddle).signum())?;
+                }
+                intcode::HaltedState::Output(output) => {
+                    outputs.push(output);
+                    if outputs.len() == 3 {
+                        self.draw(outputs[0], outputs[1], outputs[2])?;
+                        outputs.clear();
+                    }
+                }
+            }
+        }
+        if !outputs.is_empty() {
+            return Err("Program halted part way through drawing a tile".into());
+        }
+        Ok(())
+    }
+
+    /// Number of tiles of a type on screen
+    pub fn count(&self, tile: Tile) -> usize {
+        self.screen.values().filter(|t| **t == tile).count()
+    }
+
+    pub fn score(&self) -> isize {
+        self.score
+    }
+
+    /// Renders the score and screen to a visible string "image"
+    pub fn frame(&self) -> String {
+        let max_x = self.screen.keys().map(|(x, _)| *x).max().unwrap_or(-1);
+        let max_y = self.screen.keys().map(|(_, y)| *y).max().unwrap_or(-1);
+
+        let mut display = format!("Score: {}", self.score);
+        for y in 0..=max_y {
+            display.push('\n');
+            for x in 0..=max_x {
+                display.push(self.screen.get(&(x, y)).unwrap_or(&Tile::Empty).render());
+            }
+        }
+        display
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Program outputting each value in order, then halting
+    fn outputs(values: &[isize]) -> String {
+        values
+            .iter()
+            .map(|value| format!("104,{},", value))
+            .collect()
+    }
+
+    #[test]
+    fn test_count() {
+        let program = outputs(&[1, 2, 3, 6, 5, 4, 0, 0, 2, 1, 0, 2, 1, 0, 1]) + "99";
+        let mem = intcode::parse_mem(program.as_bytes()).unwrap();
+        let mut arcade = Arcade::new(intcode::Computer::new(&mem));
+        arcade.play(None).unwrap();
+
+        assert_eq!(arcade.count(Tile::Block), 1);
+        assert_eq!(arcade.count(Tile::Wall), 1);
+        assert_eq!((arcade.ball, arcade.paddle), (6, 1));
+    }
+
+    #[test]
+    fn test_autoplay() {
+        // draws a wall, ball and paddle, then reports the joystick position as the score
+        let program =
+            outputs(&[0, 0, 1, 3, 1, 4, 1, 2, 3]) + "3,100," + &outputs(&[-1, 0]) + "4,100,99";
+        let mem = intcode::parse_mem(program.as_bytes()).unwrap();
+        let mut arcade = Arcade::new(intcode::Computer::new(&mem));
+
+        let mut screen = Vec::new();
+        arcade.play(Some(&mut screen)).unwrap();
+
+        assert_eq!(arcade.score(), 1);
+        assert_eq!(
+            String::from_utf8(screen).unwrap(),
+            format!("{}Score: 0\n#   \n   o\n -  ", CLEAR_SCREEN)
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use std::error;
+use std::io;
+
+use crate::shared::intcode;
+
+mod arcade;
+
+pub fn part01<T>(reader: T) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let mut arcade = arcade::Arcade::new(intcode::Computer::new(&mem));
+    arcade.play(None)?;
+
+    Ok(format!(
+        "Day 13 Part 01 Answer: {}",
+        arcade.count(arcade::Tile::Block)
+    ))
+}
+
+pub fn part02<T>(reader: T, render: bool) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mut mem = intcode::parse_mem(reader)?;
+    // insert quarters to play for free
+    mem.insert(0, 2);
+
+    let mut arcade = arcade::Arcade::new(intcode::Computer::new(&mem));
+    let mut stdout = io::stdout();
+    arcade.play(if render {
+        Some(&mut stdout as &mut dyn io::Write)
+    } else {
+        None
+    })?;
+
+    Ok(format!("Day 13 Part 02 Answer: {}", arcade.score()))
+}
@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+use std::error;
+use std::io;
+
+mod nanofactory;
+
+pub fn part01<T>(reader: T) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let factory = nanofactory::parse_reactions(reader)?;
+    let ore = factory.produce(1, &mut HashMap::new())?;
+
+    Ok(format!("Day 14 Part 01 Answer: {}", ore))
+}
+
+pub fn part02<T>(reader: T, ore: u64) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let factory = nanofactory::parse_reactions(reader)?;
+    let fuel = factory.max_fuel(ore)?;
+
+    Ok(format!("Day 14 Part 02 Answer: {}", fuel))
+}
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::{error, fmt, io};
+
+const ORE: &str = "ORE";
+const FUEL: &str = "FUEL";
+
+/// Error raised when reactions are unable to be parsed or used
+#[derive(Debug, PartialEq)]
+pub enum ReactionError {
+    /// a line could not be parsed as a reaction
+    Parse { line: usize, reason: String },
+    /// more than one reaction produces the chemical
+    Duplicate(String),
+    /// no reaction produces the chemical
+    Unknown(String),
+    /// the chemical is needed, directly or indirectly, to produce itself
+    Cycle(String),
+    /// the amount needed does not fit in 64 bits
+    Overflow,
+}
+
+impl fmt::Display for ReactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { line, reason } => write!(f, "Unable to parse line {}: {}", line, reason),
+            Self::Duplicate(chemical) => {
+                write!(f, "{} is produced by more than one reaction", chemical)
+            }
+            Self::Unknown(chemical) => write!(f, "No reaction produces {}", chemical),
+            Self::Cycle(chemical) => write!(f, "{} is needed to produce itself", chemical),
+            Self::Overflow => write!(f, "Amount of chemicals needed is too large"),
+        }
+    }
+}
+
+impl error::Error for ReactionError {}
+
+struct Reaction {
+    quantity: u64,
+    inputs: Vec<(String, u64)>,
+}
+
+pub struct Nanofactory {
+    reactions: HashMap<String, Reaction>,
+    /// chemicals ordered so each comes before any chemical it is made from
+    order: Vec<String>,
+}
+
+impl Nanofactory {
+    /// Order chemicals needed for FUEL so every consumer comes before what it consumes
+    /// a depth first search records each chemical once all of its inputs are recorded
+    fn topological_order(
+        reactions: &HashMap<String, Reaction>,
+    ) -> Result<Vec<String>, ReactionError> {
+        #[derive(PartialEq)]
+        enum Visit {
+            InProgress,
+            Done,
+        }
+
+        fn visit(
+            chemical: &str,
+            reactions: &HashMap<String, Reaction>,
+            visits: &mut HashMap<String, Visit>,
+            order: &mut Vec<String>,
+        ) -> Result<(), ReactionError> {
+            match visits.get(chemical) {
+                Some(Visit::Done) => return Ok(()),
+                Some(Visit::InProgress) => return Err(ReactionError::Cycle(chemical.to_string())),
+                None => (),
+            }
+            if chemical == ORE {
+                return Ok(());
+            }
+            let reaction = reactions
+                .get(chemical)
+                .ok_or_else(|| ReactionError::Unknown(chemical.to_string()))?;
+
+            visits.insert(chemical.to_string(), Visit::InProgress);
+            for (input, _) in &reaction.inputs {
+                visit(input, reactions, visits, order)?;
+            }
+            visits.insert(chemical.to_string(), Visit::Done);
+            order.push(chemical.to_string());
+            Ok(())
+        }
+
+        let mut order = Vec::with_capacity(reactions.len());
+        visit(FUEL, reactions, &mut HashMap::new(), &mut order)?;
+        order.reverse();
+        Ok(order)
+    }
+
+    /// ORE needed to produce an amount of FUEL
+    /// leftover chemicals are used up before running any reactions and surplus is added back
+    /// chemicals are processed in topological order so each is produced once in full
+    pub fn produce(
+        &self,
+        fuel: u64,
+        leftovers: &mut HashMap<String, u64>,
+    ) -> Result<u64, ReactionError> {
+        let mut needed: HashMap<&str, u64> = HashMap::new();
+        needed.insert(FUEL, fuel);
+
+        for chemical in &self.order {
+            let leftover = leftovers.entry(chemical.to_string()).or_insert(0);
+            let amount = needed.get(chemical.as_str()).copied().unwrap_or(0);
+            let used = amount.min(*leftover);
+            *leftover -= used;
+            let amount = amount - used;
+
+            let reaction = &self.reactions[chemical];
+            let batches = amount.div_ceil(reaction.quantity);
+            *leftover += batches
+                .checked_mul(reaction.quantity)
+                .ok_or(ReactionError::Overflow)?
+                - amount;
+            for (input, quantity) in &reaction.inputs {
+                let total = needed.entry(input).or_insert(0);
+                *total = batches
+                    .checked_mul(*quantity)
+                    .and_then(|amount| total.checked_add(amount))
+                    .ok_or(ReactionError::Overflow)?;
+            }
+        }
+
+        Ok(needed.get(ORE).copied().unwrap_or(0))
+    }
+
+    /// Most FUEL that can be produced from an amount of ORE
+    /// found by doubling an upper bound and then binary searching
+    pub fn max_fuel(&self, ore: u64) -> Result<u64, ReactionError> {
+        let affordable = |fuel: u64| match self.produce(fuel, &mut HashMap::new()) {
+            Ok(needed) => Ok(needed <= ore),
+            Err(ReactionError::Overflow) => Ok(false),
+            Err(e) => Err(e),
+        };
+
+        let mut low = 0;
+        let mut high = 1;
+        while affordable(high)? {
+            low = high;
+            high = high.checked_mul(2).ok_or(ReactionError::Overflow)?;
+        }
+        // low is always affordable and high never is
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if affordable(middle)? {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        Ok(low)
+    }
+}
+
+/// Parse a chemical with its quantity, as 7 A
+fn parse_chemical(token: &str, line: usize) -> Result<(String, u64), ReactionError> {
+    let error = |reason: String| ReactionError::Parse { line, reason };
+
+    let mut parts = token.split_whitespace();
+    let quantity = parts
+        .next()
+        .ok_or_else(|| error("Missing chemical".to_string()))?;
+    let quantity = quantity
+        .parse()
+        .map_err(|e| error(format!("Invalid quantity {}: {}", quantity, e)))?;
+    let name = parts
+        .next()
+        .ok_or_else(|| error(format!("Missing chemical name in {}", token.trim())))?;
+    if parts.next().is_some() || quantity == 0 {
+        return Err(error(format!("Invalid chemical {}", token.trim())));
+    }
+    Ok((name.to_string(), quantity))
+}
+
+/// Parse reactions, one per line as 7 A, 1 E => 1 FUEL
+pub fn parse_reactions<T>(reader: T) -> Result<Nanofactory, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mut reactions = HashMap::new();
+
+    for (index, res) in reader.lines().enumerate() {
+        let line = res?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let number = index + 1;
+
+        let mut sides = line.split("=>");
+        let (inputs, output) = match (sides.next(), sides.next(), sides.next()) {
+            (Some(inputs), Some(output), None) => (inputs, output),
+            _ => {
+                return Err(ReactionError::Parse {
+                    line: number,
+                    reason: "Expected inputs => output".to_string(),
+                }
+                .into())
+            }
+        };
+
+        let inputs = inputs
+            .split(',')
+            .map(|token| parse_chemical(token, number))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (name, quantity) = parse_chemical(output, number)?;
+
+        if reactions.contains_key(&name) {
+            return Err(ReactionError::Duplicate(name).into());
+        }
+        reactions.insert(name, Reaction { quantity, inputs });
+    }
+
+    let order = Nanofactory::topological_order(&reactions)?;
+    Ok(Nanofactory { reactions, order })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LARGE_EXAMPLE: &str = "157 ORE => 5 NZVS
+165 ORE => 6 DCFZ
+44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL
+12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ
+179 ORE => 7 PSHF
+177 ORE => 5 HKGWZ
+7 DCFZ, 7 PSHF => 2 XJWVT
+165 ORE => 2 GPVTF
+3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT";
+
+    #[test]
+    fn test_produce() {
+        let input = "10 ORE => 10 A
+1 ORE => 1 B
+7 A, 1 B => 1 C
+7 A, 1 C => 1 D
+7 A, 1 D => 1 E
+7 A, 1 E => 1 FUEL"
+            .as_bytes();
+
+        let factory = parse_reactions(input).unwrap();
+        let mut leftovers = HashMap::new();
+        assert_eq!(factory.produce(1, &mut leftovers).unwrap(), 31);
+        assert_eq!(leftovers["A"], 2);
+        // the leftover A only saves ORE once enough builds up
+        assert_eq!(factory.produce(1, &mut leftovers).unwrap(), 31);
+        assert_eq!(leftovers["A"], 4);
+        assert_eq!(factory.produce(3, &mut leftovers).unwrap(), 83);
+        assert_eq!(factory.produce(3, &mut HashMap::new()).unwrap(), 93);
+
+        let input = "9 ORE => 2 A
+8 ORE => 3 B
+7 ORE => 5 C
+3 A, 4 B => 1 AB
+5 B, 7 C => 1 BC
+4 C, 1 A => 1 CA
+2 AB, 3 BC, 4 CA => 1 FUEL"
+            .as_bytes();
+
+        let factory = parse_reactions(input).unwrap();
+        assert_eq!(factory.produce(1, &mut HashMap::new()).unwrap(), 165);
+
+        let factory = parse_reactions(LARGE_EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(factory.produce(1, &mut HashMap::new()).unwrap(), 13312);
+    }
+
+    #[test]
+    fn test_max_fuel() {
+        let factory = parse_reactions(LARGE_EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(factory.max_fuel(1_000_000_000_000).unwrap(), 82_892_753);
+        assert_eq!(factory.max_fuel(13311).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |input: &str| {
+            *parse_reactions(input.as_bytes())
+                .err()
+                .unwrap()
+                .downcast::<ReactionError>()
+                .unwrap()
+        };
+
+        assert_eq!(
+            error("1 ORE => 1 A\n2 B => 1 FUEL"),
+            ReactionError::Unknown("B".to_string())
+        );
+        assert_eq!(
+            error("1 B => 1 A\n1 A => 1 B\n1 A => 1 FUEL"),
+            ReactionError::Cycle("A".to_string())
+        );
+        assert_eq!(
+            error("1 ORE => 1 A\n1 ORE => 2 A\n1 A => 1 FUEL"),
+            ReactionError::Duplicate("A".to_string())
+        );
+        assert_eq!(
+            error("1 ORE => 1 A\n\n1 A, => 1 FUEL").to_string(),
+            "Unable to parse line 3: Missing chemical"
+        );
+    }
+}
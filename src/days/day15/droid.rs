@@ -0,0 +1,254 @@
+use std::collections::{HashMap, VecDeque};
+use std::error;
+
+use crate::shared::intcode;
+
+/// Location in the area, x grows to the right and y grows downwards
+type Point = (isize, isize);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [Self::North, Self::South, Self::West, Self::East];
+
+    /// Movement command understood by the droid
+    fn command(self) -> isize {
+        match self {
+            Self::North => 1,
+            Self::South => 2,
+            Self::West => 3,
+            Self::East => 4,
+        }
+    }
+
+    fn reverse(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::West => Self::East,
+            Self::East => Self::West,
+        }
+    }
+
+    fn step(self, (x, y): Point) -> Point {
+        match self {
+            Self::North => (x, y - 1),
+            Self::South => (x, y + 1),
+            Self::West => (x - 1, y),
+            Self::East => (x + 1, y),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cell {
+    Wall,
+    Open,
+    Oxygen,
+}
+
+impl Cell {
+    /// Cell from the droid's status code
+    fn new(status: isize) -> Result<Self, String> {
+        match status {
+            0 => Ok(Self::Wall),
+            1 => Ok(Self::Open),
+            2 => Ok(Self::Oxygen),
+            x => Err(format!("Unexpected Status Code: {}", x)),
+        }
+    }
+}
+
+/// Map of the area discovered by the droid, starting from (0, 0)
+pub struct Area {
+    cells: HashMap<Point, Cell>,
+}
+
+impl Area {
+    /// Distance from a point to every reachable cell
+    fn distances(&self, from: Point) -> HashMap<Point, usize> {
+        let mut distances = HashMap::new();
+        let mut next = VecDeque::new();
+        distances.insert(from, 0);
+        next.push_back(from);
+
+        while let Some(point) = next.pop_front() {
+            let distance = distances[&point] + 1;
+            for direction in &Direction::ALL {
+                let neighbour = direction.step(point);
+                let open = matches!(
+                    self.cells.get(&neighbour),
+                    Some(Cell::Open) | Some(Cell::Oxygen)
+                );
+                if open && !distances.contains_key(&neighbour) {
+                    distances.insert(neighbour, distance);
+                    next.push_back(neighbour);
+                }
+            }
+        }
+        distances
+    }
+
+    fn oxygen_system(&self) -> Result<Point, String> {
+        self.cells
+            .iter()
+            .find(|(_, cell)| **cell == Cell::Oxygen)
+            .map(|(point, _)| *point)
+            .ok_or_else(|| "Unable to find the oxygen system".to_string())
+    }
+
+    /// Fewest movement commands from the start to the oxygen system
+    pub fn shortest_path(&self) -> Result<usize, String> {
+        let oxygen = self.oxygen_system()?;
+        self.distances((0, 0))
+            .get(&oxygen)
+            .copied()
+            .ok_or_else(|| "Unable to reach the oxygen system".to_string())
+    }
+
+    /// Minutes for oxygen to spread from the oxygen system to every open cell
+    pub fn oxygenate(&self) -> Result<usize, String> {
+        let oxygen = self.oxygen_system()?;
+        Ok(self.distances(oxygen).values().max().copied().unwrap_or(0))
+    }
+
+    /// Renders the discovered area to a visible string "image"
+    /// the start is marked `D`, the oxygen system `O` and undiscovered cells are blank
+    pub fn render(&self) -> String {
+        let min_x = self.cells.keys().map(|(x, _)| *x).min().unwrap_or(0);
+        let max_x = self.cells.keys().map(|(x, _)| *x).max().unwrap_or(0);
+        let min_y = self.cells.keys().map(|(_, y)| *y).min().unwrap_or(0);
+        let max_y = self.cells.keys().map(|(_, y)| *y).max().unwrap_or(0);
+
+        let mut display = String::new();
+        for y in min_y..=max_y {
+            display.push('\n');
+            for x in min_x..=max_x {
+                display.push(match self.cells.get(&(x, y)) {
+                    _ if (x, y) == (0, 0) => 'D',
+                    Some(Cell::Wall) => '#',
+                    Some(Cell::Open) => '.',
+                    Some(Cell::Oxygen) => 'O',
+                    None => ' ',
+                });
+            }
+        }
+        display
+    }
+}
+
+/// Map the whole area with a depth first search, backtracking once every neighbour is known
+/// `travel` attempts to move the droid one cell and reports what was found there
+fn map_area<F>(mut travel: F) -> Result<Area, Box<dyn error::Error>>
+where
+    F: FnMut(Direction) -> Result<Cell, Box<dyn error::Error>>,
+{
+    let mut cells = HashMap::new();
+    let mut position = (0, 0);
+    let mut path: Vec<Direction> = Vec::new();
+    cells.insert(position, Cell::Open);
+
+    loop {
+        let unexplored = Direction::ALL
+            .iter()
+            .find(|direction| !cells.contains_key(&direction.step(position)));
+
+        match unexplored {
+            Some(direction) => {
+                let cell = travel(*direction)?;
+                cells.insert(direction.step(position), cell);
+                if cell != Cell::Wall {
+                    position = direction.step(position);
+                    path.push(*direction);
+                }
+            }
+            None => match path.pop() {
+                Some(direction) => {
+                    travel(direction.reverse())?;
+                    position = direction.reverse().step(position);
+                }
+                None => break,
+            },
+        }
+    }
+    Ok(Area { cells })
+}
+
+/// Drive the droid around the whole area
+pub fn explore(mut computer: intcode::Computer) -> Result<Area, Box<dyn error::Error>> {
+    map_area(|direction| {
+        match computer.run()? {
+            intcode::HaltedState::Input => computer.send_input(direction.command())?,
+            state => return Err(format!("Expected droid to request input, got {:?}", state).into()),
+        }
+        match computer.run()? {
+            intcode::HaltedState::Output(status) => Ok(Cell::new(status)?),
+            state => Err(format!("Expected droid to report status, got {:?}", state).into()),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Map an area from text, the droid starts at `D` and `O` is the oxygen system
+    fn map_text(text: &str) -> Area {
+        let mut grid = HashMap::new();
+        let mut position = (0, 0);
+        for (y, line) in text.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let point = (x as isize, y as isize);
+                if c == 'D' {
+                    position = point;
+                }
+                grid.insert(point, c);
+            }
+        }
+
+        map_area(|direction| {
+            let next = direction.step(position);
+            let cell = match grid.get(&next) {
+                Some('.') | Some('D') => Cell::Open,
+                Some('O') => Cell::Oxygen,
+                _ => Cell::Wall,
+            };
+            if cell != Cell::Wall {
+                position = next;
+            }
+            Ok(cell)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let area = map_text(
+            " ##
+#D.##
+#.#..#
+#.O.#
+ ###",
+        );
+        assert_eq!(area.shortest_path().unwrap(), 3);
+        assert_eq!(area.render(), "\n ##   \n#D.## \n#.#..#\n#.O.# \n ###  ");
+    }
+
+    #[test]
+    fn test_oxygenate() {
+        let area = map_text(
+            " ##
+#..##
+#.#..#
+#DO.#
+ ###",
+        );
+        assert_eq!(area.oxygenate().unwrap(), 4);
+    }
+}
@@ -0,0 +1,36 @@
+use std::error;
+use std::io;
+
+use crate::shared::intcode;
+
+mod droid;
+
+pub fn part01<T>(reader: T, map: bool) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let area = droid::explore(intcode::Computer::new(&mem))?;
+    let steps = area.shortest_path()?;
+
+    let mut answer = format!("Day 15 Part 01 Answer: {}", steps);
+    if map {
+        answer.push_str(&area.render());
+    }
+    Ok(answer)
+}
+
+pub fn part02<T>(reader: T, map: bool) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let area = droid::explore(intcode::Computer::new(&mem))?;
+    let minutes = area.oxygenate()?;
+
+    let mut answer = format!("Day 15 Part 02 Answer: {}", minutes);
+    if map {
+        answer.push_str(&area.render());
+    }
+    Ok(answer)
+}
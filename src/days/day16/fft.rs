@@ -0,0 +1,151 @@
+use std::io;
+
+/// Digits in a message
+const MESSAGE_LENGTH: usize = 8;
+
+/// Digits of the first line of input
+pub fn parse_signal<T>(reader: T) -> Result<Vec<u8>, String>
+where
+    T: io::BufRead,
+{
+    reader
+        .lines()
+        .next()
+        .ok_or("Unable to parse! Empty File?")?
+        .map_err(|x| format!("Error Parsing File: {}", x))?
+        .trim()
+        .chars()
+        .map(|c| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or(format!("Unexpected Signal Digit: {}", c))
+        })
+        .collect()
+}
+
+/// Digits joined into a string
+pub fn message(digits: &[u8]) -> String {
+    digits.iter().map(|d| (b'0' + d) as char).collect()
+}
+
+/// Apply a single phase
+/// output digit `n` (counting from 1) repeats each pattern value `n` times, so the pattern is
+/// made of runs of `n` digits that are added, skipped, subtracted then skipped again,
+/// each run is summed in one step using prefix sums
+fn phase(signal: &[u8]) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(signal.len() + 1);
+    prefix.push(0i64);
+    for digit in signal {
+        prefix.push(prefix[prefix.len() - 1] + *digit as i64);
+    }
+    let sum = |start: usize, n: usize| {
+        let end = (start + n).min(signal.len());
+        prefix[end] - prefix[start]
+    };
+
+    (1..=signal.len())
+        .map(|n| {
+            let mut total = 0;
+            // the pattern is shifted left by one, the first run of `1`s starts at n - 1
+            let mut start = n - 1;
+            while start < signal.len() {
+                total += sum(start, n);
+                if start + 2 * n < signal.len() {
+                    total -= sum(start + 2 * n, n);
+                }
+                start += 4 * n;
+            }
+            (total.abs() % 10) as u8
+        })
+        .collect()
+}
+
+/// Apply a number of phases to the signal
+pub fn transform(signal: &[u8], phases: usize) -> Vec<u8> {
+    (0..phases).fold(signal.to_vec(), |signal, _| phase(&signal))
+}
+
+/// Message from the first digits of the signal once the phases are applied
+pub fn first_message(signal: &[u8], phases: usize) -> Result<String, String> {
+    if signal.len() < MESSAGE_LENGTH {
+        return Err(format!(
+            "Signal must have at least {} digits to hold a message",
+            MESSAGE_LENGTH
+        ));
+    }
+    Ok(message(&transform(signal, phases)[..MESSAGE_LENGTH]))
+}
+
+/// Message from the signal repeated `repeat` times, found at the offset given by its first seven digits
+/// the offset must be in the second half of the signal, where every pattern is `0`s followed by `1`s,
+/// so each digit is just the sum of itself and the digits after it
+pub fn decode(signal: &[u8], phases: usize, repeat: usize) -> Result<String, String> {
+    if signal.len() < 7 {
+        return Err("Signal is too short to hold a message offset".to_string());
+    }
+    let offset = signal[..7]
+        .iter()
+        .fold(0, |offset, digit| offset * 10 + *digit as usize);
+    let length = signal.len() * repeat;
+    if offset * 2 < length || offset + MESSAGE_LENGTH > length {
+        return Err(format!(
+            "Message offset {} must be in the second half of the {} digit signal",
+            offset, length
+        ));
+    }
+
+    let mut tail: Vec<u8> = (offset..length)
+        .map(|index| signal[index % signal.len()])
+        .collect();
+    for _ in 0..phases {
+        let mut sum = 0;
+        for digit in tail.iter_mut().rev() {
+            sum = (sum + *digit) % 10;
+            *digit = sum;
+        }
+    }
+    Ok(message(&tail[..MESSAGE_LENGTH]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn signal(digits: &str) -> Vec<u8> {
+        parse_signal(digits.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_transform() {
+        let input = signal("12345678");
+        assert_eq!(message(&transform(&input, 1)), "48226158");
+        assert_eq!(message(&transform(&input, 4)), "01029498");
+
+        let examples = [
+            ("80871224585914546619083218645595", "24176176"),
+            ("19617804207202209144916044189917", "73745418"),
+            ("69317163492948606335995924319873", "52432133"),
+        ];
+        for (input, expected) in examples.iter() {
+            assert_eq!(first_message(&signal(input), 100).unwrap(), *expected);
+        }
+
+        assert!(first_message(&signal("1234567"), 100).is_err());
+        assert!(first_message(&[], 100).is_err());
+    }
+
+    #[test]
+    fn test_decode() {
+        let examples = [
+            ("03036732577212944063491565474664", "84462026"),
+            ("02935109699940807407585447034323", "78725270"),
+            ("03081770884921959731165446850517", "53553731"),
+        ];
+        for (input, expected) in examples.iter() {
+            assert_eq!(decode(&signal(input), 100, 10_000).unwrap(), *expected);
+        }
+
+        assert!(decode(&signal("12345678"), 100, 10_000).is_err());
+        assert!(parse_signal("12a4".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+use std::error;
+use std::io;
+
+mod fft;
+
+/// Times the signal is repeated to form the real signal in part 2
+const REPEAT: usize = 10_000;
+
+pub fn part01<T>(reader: T, phases: usize) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let signal = fft::parse_signal(reader)?;
+    let message = fft::first_message(&signal, phases)?;
+    Ok(format!("Day 16 Part 01 Answer: {}", message))
+}
+
+pub fn part02<T>(reader: T, phases: usize) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let signal = fft::parse_signal(reader)?;
+    let message = fft::decode(&signal, phases, REPEAT)?;
+    Ok(format!("Day 16 Part 02 Answer: {}", message))
+}
@@ -0,0 +1,67 @@
+use std::error;
+use std::io;
+
+use crate::shared::intcode;
+use crate::shared::intcode::ascii::{AsciiState, Terminal};
+
+mod routines;
+mod scaffold;
+
+/// Lines of the camera image, read until the program asks for input or halts
+fn camera(terminal: &mut Terminal) -> Result<Vec<String>, Box<dyn error::Error>> {
+    let mut lines = Vec::new();
+    loop {
+        match terminal.run()? {
+            AsciiState::Line(line) => lines.push(line),
+            AsciiState::Value(value) => {
+                return Err(format!("Unexpected value from camera: {}", value).into())
+            }
+            AsciiState::Input | AsciiState::Halt => return Ok(lines),
+        }
+    }
+}
+
+pub fn part01<T>(reader: T) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let mut terminal = Terminal::new(intcode::Computer::new(&mem));
+    let scaffold = scaffold::Scaffold::parse(&camera(&mut terminal)?)?;
+
+    Ok(format!("Day 17 Part 01 Answer: {}", scaffold.alignment()))
+}
+
+pub fn part02<T>(reader: T) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let mut terminal = Terminal::new(intcode::Computer::new(&mem));
+    let scaffold = scaffold::Scaffold::parse(&camera(&mut terminal)?)?;
+    let routines = routines::compress(&scaffold.path())?;
+
+    // wake up the vacuum robot
+    let mut awake = mem.clone();
+    awake.insert(0, 2);
+    let mut terminal = Terminal::new(intcode::Computer::new(&awake));
+    terminal.send_line(&routines.main)?;
+    for function in routines.functions.iter() {
+        terminal.send_line(function)?;
+    }
+    // no continuous video feed
+    terminal.send_line("n")?;
+
+    let mut dust = None;
+    loop {
+        match terminal.run()? {
+            AsciiState::Line(_) => (),
+            AsciiState::Value(value) => dust = Some(value),
+            AsciiState::Input => return Err("Robot requested more input than expected".into()),
+            AsciiState::Halt => break,
+        }
+    }
+
+    let dust = dust.ok_or("Robot halted without reporting the dust collected")?;
+    Ok(format!("Day 17 Part 02 Answer: {}", dust))
+}
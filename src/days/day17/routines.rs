@@ -0,0 +1,113 @@
+/// Most characters the robot accepts for a routine, not counting the newline
+const MAX_LENGTH: usize = 20;
+
+/// Movement functions the robot can store
+const FUNCTIONS: [&str; 3] = ["A", "B", "C"];
+
+/// Main movement routine and the movement functions it calls
+#[derive(Debug, PartialEq)]
+pub struct Routines {
+    pub main: String,
+    pub functions: [String; 3],
+}
+
+/// Search for functions covering the rest of the path within a number of calls, returning the order
+/// they are called in, the next moves either repeat a known function or start a new one while there is room
+fn search<'p>(
+    path: &'p [String],
+    functions: &mut Vec<&'p [String]>,
+    calls: usize,
+) -> Option<Vec<usize>> {
+    if path.is_empty() {
+        return Some(Vec::new());
+    }
+    if calls == 0 {
+        return None;
+    }
+
+    for index in 0..functions.len() {
+        let function = functions[index];
+        if path.starts_with(function) {
+            if let Some(mut calls) = search(&path[function.len()..], functions, calls - 1) {
+                calls.insert(0, index);
+                return Some(calls);
+            }
+        }
+    }
+
+    if functions.len() < FUNCTIONS.len() {
+        for length in 1..=path.len() {
+            if path[..length].join(",").len() > MAX_LENGTH {
+                break;
+            }
+            let index = functions.len();
+            functions.push(&path[..length]);
+            if let Some(mut calls) = search(&path[length..], functions, calls - 1) {
+                calls.insert(0, index);
+                return Some(calls);
+            }
+            functions.pop();
+        }
+    }
+    None
+}
+
+/// Split a path of moves into a main routine calling up to three movement functions
+/// every routine must fit within the robot's 20 character limit
+pub fn compress(path: &[String]) -> Result<Routines, String> {
+    let mut functions = Vec::new();
+    // calls in the main routine are single characters separated by commas
+    let calls = search(path, &mut functions, MAX_LENGTH.div_ceil(2))
+        .ok_or("Unable to compress the path into movement functions")?;
+
+    let main = calls
+        .iter()
+        .map(|index| FUNCTIONS[*index])
+        .collect::<Vec<&str>>()
+        .join(",");
+    let mut routines = Routines {
+        main,
+        functions: Default::default(),
+    };
+    for (routine, function) in routines.functions.iter_mut().zip(functions) {
+        *routine = function.join(",");
+    }
+    Ok(routines)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn moves(path: &str) -> Vec<String> {
+        path.split(',')
+            .collect::<Vec<&str>>()
+            .chunks(2)
+            .map(|pair| pair.join(","))
+            .collect()
+    }
+
+    #[test]
+    fn test_compress() {
+        let path = moves("R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2");
+        let routines = compress(&path).unwrap();
+
+        let expanded: Vec<&str> = routines
+            .main
+            .split(',')
+            .map(|call| {
+                let index = FUNCTIONS.iter().position(|f| *f == call).unwrap();
+                routines.functions[index].as_str()
+            })
+            .collect();
+        assert_eq!(expanded.join(","), path.join(","));
+        assert!(routines.main.len() <= MAX_LENGTH);
+        assert!(routines.functions.iter().all(|f| f.len() <= MAX_LENGTH));
+    }
+
+    #[test]
+    fn test_incompressible() {
+        let path = moves("R,1,R,2,R,3,R,4,R,5,R,6,R,7,R,8,R,9,R,10,R,11,R,12,R,13,R,14,R,15");
+        assert!(compress(&path).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+/// Location on the camera image, x grows to the right and y grows downwards
+type Point = (isize, isize);
+
+/// Scaffolding seen by the camera along with the vacuum robot's position and facing
+pub struct Scaffold {
+    cells: HashSet<Point>,
+    robot: Point,
+    facing: Point,
+}
+
+impl Scaffold {
+    /// Parse the camera image, `#` is scaffold and the robot is one of `^`, `v`, `<`, `>`
+    pub fn parse(lines: &[String]) -> Result<Self, String> {
+        let mut cells = HashSet::new();
+        let mut robot = None;
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let point = (x as isize, y as isize);
+                let facing = match c {
+                    '.' => continue,
+                    '#' => None,
+                    '^' => Some((0, -1)),
+                    'v' => Some((0, 1)),
+                    '<' => Some((-1, 0)),
+                    '>' => Some((1, 0)),
+                    'X' => return Err("Vacuum robot is tumbling through space".to_string()),
+                    _ => return Err(format!("Unexpected Camera Character: {}", c)),
+                };
+                if let Some(facing) = facing {
+                    if robot.replace((point, facing)).is_some() {
+                        return Err("Found more than one vacuum robot".to_string());
+                    }
+                }
+                cells.insert(point);
+            }
+        }
+
+        let (robot, facing) = robot.ok_or("Unable to find the vacuum robot")?;
+        Ok(Scaffold {
+            cells,
+            robot,
+            facing,
+        })
+    }
+
+    /// Sum of the alignment parameters, x multiplied by y, of every scaffold intersection
+    pub fn alignment(&self) -> isize {
+        self.cells
+            .iter()
+            .filter(|(x, y)| {
+                [(0, -1), (0, 1), (-1, 0), (1, 0)]
+                    .iter()
+                    .all(|(dx, dy)| self.cells.contains(&(x + dx, y + dy)))
+            })
+            .map(|(x, y)| x * y)
+            .sum()
+    }
+
+    /// Moves covering the whole scaffold, going straight over intersections and
+    /// only turning at corners, each move is a turn (`L` or `R`) followed by a number of steps
+    pub fn path(&self) -> Vec<String> {
+        let mut moves = Vec::new();
+        let (mut position, mut facing) = (self.robot, self.facing);
+        let ahead = |(x, y): Point, (dx, dy): Point| (x + dx, y + dy);
+
+        loop {
+            let left = (facing.1, -facing.0);
+            let right = (-facing.1, facing.0);
+            let (turn, direction) = if self.cells.contains(&ahead(position, left)) {
+                ('L', left)
+            } else if self.cells.contains(&ahead(position, right)) {
+                ('R', right)
+            } else {
+                break;
+            };
+
+            facing = direction;
+            let mut steps = 0;
+            while self.cells.contains(&ahead(position, facing)) {
+                position = ahead(position, facing);
+                steps += 1;
+            }
+            moves.push(format!("{},{}", turn, steps));
+        }
+        moves
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn image(text: &str) -> Vec<String> {
+        text.lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_alignment() {
+        let lines = image(
+            "..#..........
+..#..........
+#######...###
+#.#...#...#.#
+#############
+..#...#...#..
+..#####...^..",
+        );
+        assert_eq!(Scaffold::parse(&lines).unwrap().alignment(), 76);
+    }
+
+    #[test]
+    fn test_path() {
+        let lines = image(
+            "#######...#####
+#.....#...#...#
+#.....#...#...#
+......#...#...#
+......#...###.#
+......#.....#.#
+^########...#.#
+......#.#...#.#
+......#########
+........#...#..
+....#########..
+....#...#......
+....#...#......
+....#...#......
+....#####......",
+        );
+        let scaffold = Scaffold::parse(&lines).unwrap();
+        assert_eq!(
+            scaffold.path().join(","),
+            "R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2"
+        );
+
+        assert!(Scaffold::parse(&image("#.#\n..X")).is_err());
+        assert!(Scaffold::parse(&image("#.#\n..#")).is_err());
+    }
+}
@@ -0,0 +1,21 @@
+use std::error;
+use std::io;
+
+mod vault;
+
+pub fn part01<T>(reader: T) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let vault = vault::Vault::parse(reader)?;
+    Ok(format!("Day 18 Part 01 Answer: {}", vault.collect_keys()?))
+}
+
+pub fn part02<T>(reader: T) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mut vault = vault::Vault::parse(reader)?;
+    vault.split()?;
+    Ok(format!("Day 18 Part 02 Answer: {}", vault.collect_keys()?))
+}
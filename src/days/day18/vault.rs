@@ -0,0 +1,346 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::io;
+
+/// Location in the vault as (row, column)
+type Point = (usize, usize);
+
+/// Most robots searched for at once, enough for a split vault
+const MAX_ROBOTS: usize = 4;
+
+/// Shortest route from one point of interest to a key
+struct Route {
+    key: usize,
+    steps: usize,
+    /// keys needed for the doors along the way, along with any keys passed over,
+    /// a route over another key is covered by stopping at that key first
+    needs: u32,
+}
+
+pub struct Vault {
+    grid: Vec<Vec<char>>,
+    entrances: Vec<Point>,
+    keys: Vec<Option<Point>>,
+}
+
+impl Vault {
+    /// Parse the map, `@` is an entrance, lowercase letters are keys and uppercase letters are doors
+    pub fn parse<T>(reader: T) -> Result<Self, String>
+    where
+        T: io::BufRead,
+    {
+        let mut grid = Vec::new();
+        let mut entrances = Vec::new();
+        let mut keys = vec![None; 26];
+        for (row, line) in reader.lines().enumerate() {
+            let line = line.map_err(|x| format!("Error Parsing File: {}", x))?;
+            let mut cells = Vec::new();
+            for (column, c) in line.trim_end().chars().enumerate() {
+                match c {
+                    '@' => entrances.push((row, column)),
+                    'a'..='z' => {
+                        if keys[key(c)].replace((row, column)).is_some() {
+                            return Err(format!("Found more than one key {}", c));
+                        }
+                    }
+                    '#' | '.' | 'A'..='Z' => (),
+                    _ => return Err(format!("Unexpected Vault Character: {}", c)),
+                }
+                cells.push(c);
+            }
+            grid.push(cells);
+        }
+
+        if entrances.is_empty() {
+            return Err("Unable to find an entrance".to_string());
+        }
+        Ok(Vault {
+            grid,
+            entrances,
+            keys,
+        })
+    }
+
+    /// Split the vault into four by walling off the area around the entrance,
+    /// placing an entrance in each diagonal, a vault that is already split is left as is
+    pub fn split(&mut self) -> Result<(), String> {
+        match self.entrances.as_slice() {
+            [_, _, _, _] => return Ok(()),
+            [(row, column)] if *row > 0 && *column > 0 => (),
+            _ => return Err("Vault needs a single entrance to split".to_string()),
+        }
+        let (row, column) = self.entrances[0];
+        let rows = self.grid.get(row - 1..=row + 1);
+        if !matches!(rows, Some(rows) if rows.iter().all(|cells| cells.len() > column + 1)) {
+            return Err("Entrance is too close to the edge of the vault".to_string());
+        }
+
+        let mut entrances = Vec::new();
+        for r in row - 1..=row + 1 {
+            for c in column - 1..=column + 1 {
+                if r != row && c != column {
+                    self.grid[r][c] = '@';
+                    entrances.push((r, c));
+                } else {
+                    self.grid[r][c] = '#';
+                }
+            }
+        }
+        self.entrances = entrances;
+        Ok(())
+    }
+
+    fn cell(&self, (row, column): Point) -> char {
+        self.grid
+            .get(row)
+            .and_then(|cells| cells.get(column))
+            .copied()
+            .unwrap_or('#')
+    }
+
+    /// Breadth first search from a point to every key reachable from it
+    /// doors are walked through, recording the key needed to open them, a point is revisited when
+    /// reached needing keys that no earlier visit had to, so a longer route that avoids a door is
+    /// kept alongside the shortest one
+    fn routes(&self, from: Point) -> Vec<Route> {
+        let mut routes = Vec::new();
+        let mut seen: Vec<Vec<Vec<u32>>> =
+            vec![
+                vec![Vec::new(); self.grid.iter().map(Vec::len).max().unwrap_or(0)];
+                self.grid.len()
+            ];
+        let mut next = VecDeque::new();
+        seen[from.0][from.1].push(0);
+        next.push_back((from, 0, 0u32));
+
+        while let Some(((row, column), steps, mut needs)) = next.pop_front() {
+            match self.cell((row, column)) {
+                c @ 'a'..='z' if steps > 0 => {
+                    routes.push(Route {
+                        key: key(c),
+                        steps,
+                        needs,
+                    });
+                    needs |= 1 << key(c);
+                }
+                c @ 'A'..='Z' => {
+                    let needed = key(c.to_ascii_lowercase());
+                    // a door without a key can never be opened
+                    if self.keys[needed].is_none() {
+                        continue;
+                    }
+                    needs |= 1 << needed;
+                }
+                _ => (),
+            }
+
+            let neighbours = [
+                (row.wrapping_sub(1), column),
+                (row + 1, column),
+                (row, column.wrapping_sub(1)),
+                (row, column + 1),
+            ];
+            for point in neighbours.iter() {
+                if self.cell(*point) == '#' {
+                    continue;
+                }
+                // visits come in order of steps, so an earlier visit needing fewer keys is always better
+                let visits = &mut seen[point.0][point.1];
+                if visits.iter().all(|visit| visit & !needs != 0) {
+                    visits.push(needs);
+                    next.push_back((*point, steps + 1, needs));
+                }
+            }
+        }
+        routes
+    }
+
+    /// Fewest steps for the robots, one at each entrance, to collect every key
+    /// a Dijkstra search over the robots' positions and the keys collected so far,
+    /// where each move takes one robot straight to a key it can reach
+    pub fn collect_keys(&self) -> Result<usize, String> {
+        // points of interest are the entrances followed by the keys
+        let robots = self.entrances.len();
+        if robots > MAX_ROBOTS {
+            return Err(format!("Vault has more than {} entrances", MAX_ROBOTS));
+        }
+        let mut routes: Vec<Vec<Route>> = self.entrances.iter().map(|e| self.routes(*e)).collect();
+        for point in self.keys.iter() {
+            routes.push(match point {
+                Some(point) => self.routes(*point),
+                None => Vec::new(),
+            });
+        }
+
+        let all = self
+            .keys
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| point.is_some())
+            .fold(0u32, |all, (index, _)| all | 1 << index);
+
+        let mut start = [0u8; MAX_ROBOTS];
+        for (robot, position) in start.iter_mut().enumerate().take(robots) {
+            *position = robot as u8;
+        }
+
+        let mut best = HashMap::new();
+        let mut next = BinaryHeap::new();
+        best.insert((start, 0u32), 0);
+        next.push(Reverse((0, start, 0u32)));
+
+        while let Some(Reverse((steps, positions, collected))) = next.pop() {
+            if collected == all {
+                return Ok(steps);
+            }
+            if matches!(best.get(&(positions, collected)), Some(b) if *b < steps) {
+                continue;
+            }
+
+            for (robot, position) in positions.iter().enumerate().take(robots) {
+                for route in routes[*position as usize].iter() {
+                    if collected & 1 << route.key != 0 || route.needs & !collected != 0 {
+                        continue;
+                    }
+                    let mut moved = positions;
+                    moved[robot] = (robots + route.key) as u8;
+                    let state = (moved, collected | 1 << route.key);
+                    let steps = steps + route.steps;
+
+                    if !matches!(best.get(&state), Some(b) if *b <= steps) {
+                        best.insert(state, steps);
+                        next.push(Reverse((steps, state.0, state.1)));
+                    }
+                }
+            }
+        }
+        Err("Unable to collect every key".to_string())
+    }
+}
+
+/// Index of a key, `a` is 0
+fn key(c: char) -> usize {
+    (c as u8 - b'a') as usize
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn steps(map: &str, split: bool) -> usize {
+        let mut vault = Vault::parse(map.as_bytes()).unwrap();
+        if split {
+            vault.split().unwrap();
+        }
+        vault.collect_keys().unwrap()
+    }
+
+    #[test]
+    fn test_collect_keys() {
+        let examples = [
+            (
+                "#########
+#b.A.@.a#
+#########",
+                8,
+            ),
+            (
+                "########################
+#f.D.E.e.C.b.A.@.a.B.c.#
+######################.#
+#d.....................#
+########################",
+                86,
+            ),
+            (
+                "#################
+#i.G..c...e..H.p#
+########.########
+#j.A..b...f..D.o#
+########@########
+#k.E..a...g..B.n#
+########.########
+#l.F..d...h..C.m#
+#################",
+                136,
+            ),
+            (
+                "########################
+#@..............ac.GI.b#
+###d#e#f################
+###A#B#C################
+###g#h#i################
+########################",
+                81,
+            ),
+        ];
+        for (map, expected) in examples.iter() {
+            assert_eq!(steps(map, false), *expected);
+        }
+    }
+
+    #[test]
+    fn test_door_free_routes() {
+        // both ways around the loop to `a` are as short, but only the lower one avoids door `B`,
+        // and `b` can only be reached once `a` is collected
+        let map = "#####
+#.B.#
+#@#a.Ab#
+#...#
+#####";
+        assert_eq!(steps(map, false), 7);
+    }
+
+    #[test]
+    fn test_split() {
+        let examples = [
+            (
+                "#######
+#a.#Cd#
+##...##
+##.@.##
+##...##
+#cB#Ab#
+#######",
+                8,
+            ),
+            (
+                "###############
+#d.ABC.#.....a#
+######@#@######
+###############
+######@#@######
+#b.....#.....c#
+###############",
+                24,
+            ),
+            (
+                "#############
+#g#f.D#..h#l#
+#F###e#E###.#
+#dCba@#@BcIJ#
+#############
+#nK.L@#@G...#
+#M###N#H###.#
+#o#m..#i#jk.#
+#############",
+                72,
+            ),
+        ];
+        for (map, expected) in examples.iter() {
+            assert_eq!(steps(map, true), *expected);
+        }
+
+        let mut vault = Vault::parse("@..\n...".as_bytes()).unwrap();
+        assert!(vault.split().is_err());
+        let mut vault = Vault::parse("...\n.@.".as_bytes()).unwrap();
+        assert!(vault.split().is_err());
+
+        // a failed split leaves the vault as it was
+        let map = "###\n#@#\n#a";
+        let mut vault = Vault::parse(map.as_bytes()).unwrap();
+        assert!(vault.split().is_err());
+        assert_eq!(vault.grid, Vault::parse(map.as_bytes()).unwrap().grid);
+        assert_eq!(vault.collect_keys().unwrap(), 1);
+    }
+}
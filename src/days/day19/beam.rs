@@ -0,0 +1,138 @@
+use std::error;
+
+use crate::shared::intcode;
+
+/// Consecutive rows without any beam found before giving up the search for a square
+const MAX_EMPTY_ROWS: usize = 50;
+
+/// Rows followed down the beam before giving up the search for a square
+const MAX_ROWS: usize = 100_000;
+
+/// Drone system, the program handles a single probe before halting
+pub struct Drone<'a> {
+    computer: intcode::Computer<'a>,
+}
+
+impl<'a> Drone<'a> {
+    pub fn new(computer: intcode::Computer<'a>) -> Self {
+        Self { computer }
+    }
+
+    /// Deploy the drone to a point, reporting if it is pulled by the tractor beam
+    pub fn pulled(&mut self, x: usize, y: usize) -> Result<bool, Box<dyn error::Error>> {
+        self.computer.reset();
+        let output = self
+            .computer
+            .outputs(vec![x as isize, y as isize])
+            .next()
+            .ok_or("Drone halted without reporting")??;
+
+        match output {
+            0 => Ok(false),
+            1 => Ok(true),
+            x => Err(format!("Unexpected Drone Status: {}", x).into()),
+        }
+    }
+}
+
+/// Points affected by the beam in the `size` by `size` area closest to the emitter
+pub fn count<F>(size: usize, mut pulled: F) -> Result<usize, Box<dyn error::Error>>
+where
+    F: FnMut(usize, usize) -> Result<bool, Box<dyn error::Error>>,
+{
+    let mut affected = 0;
+    for y in 0..size {
+        for x in 0..size {
+            if pulled(x, y)? {
+                affected += 1;
+            }
+        }
+    }
+    Ok(affected)
+}
+
+/// Top left corner of the closest `size` by `size` square that fits in the beam
+/// follows the beam's lower edge down row by row, a square whose bottom left corner is on the
+/// edge fits if its top right corner is also in the beam
+/// rows without any beam found close to the previous edge, as can happen near the emitter, are skipped,
+/// the search fails once too many are skipped in a row or the beam never widens enough
+pub fn fit<F>(size: usize, mut pulled: F) -> Result<(usize, usize), Box<dyn error::Error>>
+where
+    F: FnMut(usize, usize) -> Result<bool, Box<dyn error::Error>>,
+{
+    if size == 0 {
+        return Err("Square must have a size".into());
+    }
+
+    let mut edge = 0;
+    let mut empty_rows = 0;
+    for y in size - 1..size - 1 + MAX_ROWS {
+        let found = (edge..=edge + y).find_map(|x| match pulled(x, y) {
+            Ok(true) => Some(Ok(x)),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        });
+        edge = match found {
+            Some(x) => x?,
+            None => {
+                empty_rows += 1;
+                if empty_rows == MAX_EMPTY_ROWS {
+                    return Err(
+                        format!("Beam not found in {} rows up to row {}", empty_rows, y).into(),
+                    );
+                }
+                continue;
+            }
+        };
+        empty_rows = 0;
+
+        let top = y + 1 - size;
+        if pulled(edge + size - 1, top)? {
+            return Ok((edge, top));
+        }
+    }
+    Err(format!(
+        "No {} by {} square fits in the first {} rows of the beam",
+        size, size, MAX_ROWS
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Beam widening between two slopes, starting with a few empty rows
+    fn beam(x: usize, y: usize) -> Result<bool, Box<dyn error::Error>> {
+        Ok(y >= 4 && 3 * x >= 2 * y && 4 * x <= 5 * y)
+    }
+
+    #[test]
+    fn test_count() {
+        // pulls every point below the diagonal, resetting the computer for each probe
+        let mem = intcode::parse_mem("3,100,3,101,7,101,100,102,4,102,99".as_bytes()).unwrap();
+        let mut drone = Drone::new(intcode::Computer::new(&mem));
+        assert_eq!(count(5, |x, y| drone.pulled(x, y)).unwrap(), 10);
+
+        assert_eq!(count(10, beam).unwrap(), 22);
+    }
+
+    #[test]
+    fn test_fit() {
+        for size in 1..12 {
+            // closest square found by checking every corner in the beam
+            let expected = (0..200)
+                .flat_map(|y| (0..200).map(move |x| (x, y)))
+                .filter(|(x, y)| {
+                    (0..size).all(|dy| (0..size).all(|dx| beam(x + dx, y + dy).unwrap()))
+                })
+                .min_by_key(|(x, y)| x + y)
+                .unwrap();
+            assert_eq!(fit(size, beam).unwrap(), expected);
+        }
+
+        assert!(fit(3, |_, _| Ok(false)).is_err());
+        // a beam one point wide never fits a square
+        assert!(fit(2, |x, y| Ok(x == y)).is_err());
+    }
+}
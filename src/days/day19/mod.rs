@@ -0,0 +1,28 @@
+use std::error;
+use std::io;
+
+use crate::shared::intcode;
+
+mod beam;
+
+pub fn part01<T>(reader: T, size: usize) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let mut drone = beam::Drone::new(intcode::Computer::new(&mem));
+    let affected = beam::count(size, |x, y| drone.pulled(x, y))?;
+
+    Ok(format!("Day 19 Part 01 Answer: {}", affected))
+}
+
+pub fn part02<T>(reader: T, size: usize) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let mut drone = beam::Drone::new(intcode::Computer::new(&mem));
+    let (x, y) = beam::fit(size, |x, y| drone.pulled(x, y))?;
+
+    Ok(format!("Day 19 Part 02 Answer: {}", x * 10000 + y))
+}
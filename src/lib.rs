@@ -1,6 +1,7 @@
 mod cli;
 pub mod days;
-mod shared;
+pub mod shared;
+pub mod tools;
 
 pub fn run() {
     match cli::run() {
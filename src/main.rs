@@ -1,5 +1,3 @@
-use advent_of_code_2019_rust;
-
 fn main() {
     advent_of_code_2019_rust::run();
 }
@@ -0,0 +1,144 @@
+use std::error;
+
+use futures::stream::{self, LocalBoxStream, Stream, StreamExt};
+
+use super::{Computer, HaltedState};
+
+const INPUTS_EXHAUSTED: &str = "Program requested input but no inputs remain";
+
+/// Iterator over the outputs of a Computer, see `Computer::outputs`
+pub struct Outputs<'c, 'a, I> {
+    computer: &'c mut Computer<'a>,
+    inputs: I,
+    done: bool,
+}
+
+impl<'c, 'a, I> Iterator for Outputs<'c, 'a, I>
+where
+    I: Iterator<Item = isize>,
+{
+    type Item = Result<isize, Box<dyn error::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        loop {
+            let result = match self.computer.run() {
+                Ok(HaltedState::Output(output)) => return Some(Ok(output)),
+                Ok(HaltedState::Input) => match self.inputs.next() {
+                    Some(input) => match self.computer.send_input(input) {
+                        Ok(()) => continue,
+                        Err(e) => Some(Err(e)),
+                    },
+                    None => Some(Err(INPUTS_EXHAUSTED.into())),
+                },
+                Ok(HaltedState::Halt) => None,
+                Err(e) => Some(Err(e)),
+            };
+            self.done = true;
+            return result;
+        }
+    }
+}
+
+impl<'a> Computer<'a> {
+    /// Run until halted, feeding inputs as they are requested and yielding each output
+    /// running out of inputs or a failed instruction is yielded as an error, ending the iterator
+    pub fn outputs<I>(&mut self, inputs: I) -> Outputs<'_, 'a, I::IntoIter>
+    where
+        I: IntoIterator<Item = isize>,
+    {
+        Outputs {
+            computer: self,
+            inputs: inputs.into_iter(),
+            done: false,
+        }
+    }
+
+    /// Async version of `outputs`, inputs are awaited from a stream as they are requested
+    /// the Computer itself runs synchronously between inputs, so any local executor will do
+    pub fn output_stream<'c, S>(
+        &'c mut self,
+        inputs: S,
+    ) -> LocalBoxStream<'c, Result<isize, Box<dyn error::Error>>>
+    where
+        S: Stream<Item = isize> + Unpin + 'c,
+    {
+        stream::unfold(Some((self, inputs)), |state| async move {
+            let (computer, mut inputs) = state?;
+            loop {
+                match computer.run() {
+                    Ok(HaltedState::Output(output)) => {
+                        return Some((Ok(output), Some((computer, inputs))))
+                    }
+                    Ok(HaltedState::Input) => match inputs.next().await {
+                        Some(input) => {
+                            if let Err(e) = computer.send_input(input) {
+                                return Some((Err(e), None));
+                            }
+                        }
+                        None => return Some((Err(INPUTS_EXHAUSTED.into()), None)),
+                    },
+                    Ok(HaltedState::Halt) => return None,
+                    Err(e) => return Some((Err(e), None)),
+                }
+            }
+        })
+        .boxed_local()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::channel::mpsc;
+    use futures::executor;
+
+    use super::super::parse_mem;
+    use super::*;
+
+    /// day 7 amplifier program, feedback loop from phase settings 9,8,7,6,5 gives 139629729
+    const AMPLIFIER: &str = "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,\
+                             28,-1,28,1005,28,6,99,0,0,5";
+
+    #[test]
+    fn test_outputs() {
+        // outputs each input doubled until given a zero
+        let mem = parse_mem("3,20,1006,20,14,1002,20,2,21,4,21,1105,1,0,99".as_bytes()).unwrap();
+
+        let mut computer = Computer::new(&mem);
+        let outputs: Vec<isize> = computer
+            .outputs(vec![1, 2, 3, 0])
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(outputs, vec![2, 4, 6]);
+
+        let mut computer = Computer::new(&mem);
+        let mut outputs = computer.outputs(vec![5]);
+        assert_eq!(outputs.next().unwrap().unwrap(), 10);
+        assert!(outputs.next().unwrap().is_err());
+        assert!(outputs.next().is_none());
+    }
+
+    #[test]
+    fn test_output_stream() {
+        let mem = parse_mem(AMPLIFIER.as_bytes()).unwrap();
+        let mut amps: Vec<Computer> = (0..5).map(|_| Computer::new(&mem)).collect();
+        let (first, rest) = amps.split_first_mut().unwrap();
+
+        // chain the amplifiers, the last amplifier's outputs are fed back to the first
+        let (feedback, receiver) = mpsc::unbounded();
+        let mut signals = first.output_stream(stream::iter(vec![9, 0]).chain(receiver));
+        for (amp, phase) in rest.iter_mut().zip(vec![8, 7, 6, 5]) {
+            let inputs = stream::iter(vec![phase]).chain(signals.map(|signal| signal.unwrap()));
+            signals = amp.output_stream(inputs);
+        }
+
+        let last = executor::block_on(signals.fold(None, |_, signal| {
+            let signal = signal.unwrap();
+            let _ = feedback.unbounded_send(signal);
+            async move { Some(signal) }
+        }));
+        assert_eq!(last, Some(139629729));
+    }
+}
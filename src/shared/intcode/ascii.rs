@@ -0,0 +1,141 @@
+use std::collections::VecDeque;
+use std::error;
+
+use super::{Computer, HaltedState};
+
+/// Halted States of an ASCII program
+#[derive(Debug, PartialEq)]
+pub enum AsciiState {
+    /// A line of text, without the trailing newline
+    Line(String),
+    /// An output value that is not an ASCII character
+    Value(isize),
+    /// Program is waiting for a line of input
+    Input,
+    Halt,
+}
+
+/// Convert a line of text to Intcode input values, a newline is appended
+pub fn encode(line: &str) -> Result<Vec<isize>, String> {
+    line.chars()
+        .chain(Some('\n'))
+        .map(|c| {
+            if c.is_ascii() {
+                Ok(c as isize)
+            } else {
+                Err(format!("Unable to send non ASCII character: {}", c))
+            }
+        })
+        .collect()
+}
+
+/// ASCII adapter for an Intcode Computer
+/// text is sent as lines of input codes and outputs are collected into lines
+pub struct Terminal<'a> {
+    computer: Computer<'a>,
+    inputs: VecDeque<isize>,
+    buffer: String,
+    pending: Option<AsciiState>,
+    waiting: bool,
+}
+
+impl<'a> Terminal<'a> {
+    /// Initialize a new Terminal around a Computer
+    pub fn new(computer: Computer<'a>) -> Terminal<'a> {
+        Terminal {
+            computer,
+            inputs: VecDeque::new(),
+            buffer: String::new(),
+            pending: None,
+            waiting: false,
+        }
+    }
+
+    /// Queue a line of input, it is consumed as the program requests input
+    pub fn send_line(&mut self, line: &str) -> Result<(), String> {
+        self.inputs.extend(encode(line)?);
+        Ok(())
+    }
+
+    /// Run until a line of text, a non ASCII value, an input request or a halt
+    /// any partial line is flushed before a non ASCII value, input request or halt
+    pub fn run(&mut self) -> Result<AsciiState, Box<dyn error::Error>> {
+        if let Some(state) = self.pending.take() {
+            return Ok(state);
+        }
+
+        loop {
+            if self.waiting {
+                match self.inputs.pop_front() {
+                    Some(input) => {
+                        self.computer.send_input(input)?;
+                        self.waiting = false;
+                    }
+                    None => return Ok(AsciiState::Input),
+                }
+            }
+
+            let state = match self.computer.run()? {
+                HaltedState::Output(10) => return Ok(AsciiState::Line(self.take_buffer())),
+                HaltedState::Output(output) if (0..128).contains(&output) => {
+                    self.buffer.push(output as u8 as char);
+                    continue;
+                }
+                HaltedState::Output(output) => AsciiState::Value(output),
+                HaltedState::Input => {
+                    self.waiting = true;
+                    if !self.inputs.is_empty() {
+                        continue;
+                    }
+                    AsciiState::Input
+                }
+                HaltedState::Halt => AsciiState::Halt,
+            };
+
+            if self.buffer.is_empty() {
+                return Ok(state);
+            }
+            self.pending = Some(state);
+            return Ok(AsciiState::Line(self.take_buffer()));
+        }
+    }
+
+    fn take_buffer(&mut self) -> String {
+        std::mem::take(&mut self.buffer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::parse_mem;
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("A,1").unwrap(), vec![65, 44, 49, 10]);
+        assert!(encode("é").is_err());
+    }
+
+    #[test]
+    fn test_echo() {
+        // echoes each input character until a newline is received
+        let mem = parse_mem("3,100,4,100,1008,100,10,101,1006,101,0,99".as_bytes()).unwrap();
+        let mut terminal = Terminal::new(Computer::new(&mem));
+
+        assert_eq!(terminal.run().unwrap(), AsciiState::Input);
+        terminal.send_line("Hi!").unwrap();
+        assert_eq!(terminal.run().unwrap(), AsciiState::Line("Hi!".to_string()));
+        assert_eq!(terminal.run().unwrap(), AsciiState::Halt);
+    }
+
+    #[test]
+    fn test_values() {
+        let mem = parse_mem("104,65,104,1000,104,66,99".as_bytes()).unwrap();
+        let mut terminal = Terminal::new(Computer::new(&mem));
+
+        assert_eq!(terminal.run().unwrap(), AsciiState::Line("A".to_string()));
+        assert_eq!(terminal.run().unwrap(), AsciiState::Value(1000));
+        assert_eq!(terminal.run().unwrap(), AsciiState::Line("B".to_string()));
+        assert_eq!(terminal.run().unwrap(), AsciiState::Halt);
+    }
+}
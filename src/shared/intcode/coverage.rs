@@ -0,0 +1,337 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::{Hook, InstructionSet, Parameter};
+
+/// Names of the conditional jumps in an instruction set
+const JUMP_IF_TRUE: &str = "jt";
+const JUMP_IF_FALSE: &str = "jf";
+
+/// Parameters of a conditional jump, the condition followed by the address to jump to
+const JUMP_PARAMETERS: [Parameter; 2] = [Parameter::Read, Parameter::Read];
+
+/// Opcode of a conditional jump found by name, as long as it takes a condition and an address
+fn find_jump(instruction_set: &InstructionSet, name: &str) -> Option<usize> {
+    let code = instruction_set.find(name)?;
+    if instruction_set.get(code)?.parameters() != JUMP_PARAMETERS {
+        return None;
+    }
+    Some(code)
+}
+
+/// Times a conditional jump went each way
+#[derive(Debug, Default, PartialEq)]
+pub struct Branch {
+    pub taken: usize,
+    pub not_taken: usize,
+}
+
+/// Coverage hook, records executed instructions, branch directions and memory accessed
+#[derive(Debug)]
+pub struct Coverage {
+    /// opcodes of the conditional jumps, if the instruction set has them
+    jump_if_true: Option<usize>,
+    jump_if_false: Option<usize>,
+    /// instruction address to its opcode and width
+    executed: HashMap<usize, (usize, usize)>,
+    branches: HashMap<usize, Branch>,
+    read: HashSet<usize>,
+    written: HashSet<usize>,
+}
+
+impl Hook for Coverage {
+    fn instruction(&mut self, address: usize, code: usize, args: &[isize], _next: usize) {
+        self.executed.insert(address, (code, args.len() + 1));
+
+        let taken = if Some(code) == self.jump_if_true {
+            args[0] != 0
+        } else if Some(code) == self.jump_if_false {
+            args[0] == 0
+        } else {
+            return;
+        };
+        let branch = self.branches.entry(address).or_default();
+        if taken {
+            branch.taken += 1;
+        } else {
+            branch.not_taken += 1;
+        }
+    }
+
+    fn read(&mut self, address: usize) {
+        self.read.insert(address);
+    }
+
+    fn write(&mut self, address: usize, _value: isize) {
+        self.written.insert(address);
+    }
+}
+
+/// Coverage totals of a program
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub program_cells: usize,
+    pub executed_cells: usize,
+    pub branch_directions: usize,
+    pub covered_directions: usize,
+    pub read_cells: usize,
+    pub written_cells: usize,
+}
+
+fn percent(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    count as f64 * 100.0 / total as f64
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Instructions: {}/{} cells executed ({:.1}%)",
+            self.executed_cells,
+            self.program_cells,
+            percent(self.executed_cells, self.program_cells)
+        )?;
+        writeln!(
+            f,
+            "Branches: {}/{} directions taken ({:.1}%)",
+            self.covered_directions,
+            self.branch_directions,
+            percent(self.covered_directions, self.branch_directions)
+        )?;
+        write!(
+            f,
+            "Memory: {} cells read, {} cells written",
+            self.read_cells, self.written_cells
+        )
+    }
+}
+
+impl Coverage {
+    /// Coverage of a Computer running an instruction set, its conditional jumps are found by name
+    /// and only counted as branches when they take a condition and an address
+    pub fn new(instruction_set: &InstructionSet) -> Self {
+        Self {
+            jump_if_true: find_jump(instruction_set, JUMP_IF_TRUE),
+            jump_if_false: find_jump(instruction_set, JUMP_IF_FALSE),
+            executed: HashMap::new(),
+            branches: HashMap::new(),
+            read: HashSet::new(),
+            written: HashSet::new(),
+        }
+    }
+
+    /// Branch directions taken by the conditional jump at an address
+    pub fn branch(&self, address: usize) -> Option<&Branch> {
+        self.branches.get(&address)
+    }
+
+    /// Was an instruction executed starting at this address
+    pub fn is_executed(&self, address: usize) -> bool {
+        self.executed.contains_key(&address)
+    }
+
+    /// Addresses covered by executed instructions, including their parameters
+    fn executed_cells(&self) -> HashSet<usize> {
+        self.executed
+            .iter()
+            .flat_map(|(address, (_, width))| *address..address + width)
+            .collect()
+    }
+
+    /// Summarize coverage of a program
+    pub fn summary(&self, program: &HashMap<usize, isize>) -> Summary {
+        let executed = self.executed_cells();
+
+        Summary {
+            program_cells: program.len(),
+            executed_cells: program
+                .keys()
+                .filter(|address| executed.contains(address))
+                .count(),
+            branch_directions: self.branches.len() * 2,
+            covered_directions: self
+                .branches
+                .values()
+                .map(|branch| (branch.taken > 0) as usize + (branch.not_taken > 0) as usize)
+                .sum(),
+            read_cells: self.read.len(),
+            written_cells: self.written.len(),
+        }
+    }
+
+    /// Annotated listing of a program
+    /// executed instructions are marked `+` and shown with their mnemonic,
+    /// cells never executed are marked `-`, followed by r/w if the cell was read or written
+    pub fn listing(
+        &self,
+        program: &HashMap<usize, isize>,
+        instruction_set: &InstructionSet,
+    ) -> String {
+        let end = program.keys().max().map_or(0, |address| address + 1);
+        let access = |range: std::ops::Range<usize>| {
+            let read = range.clone().any(|address| self.read.contains(&address));
+            let written = range
+                .into_iter()
+                .any(|address| self.written.contains(&address));
+            format!(
+                "{}{}",
+                if read { "r" } else { " " },
+                if written { "w" } else { " " }
+            )
+        };
+
+        let mut lines = Vec::new();
+        let mut address = 0;
+        while address < end {
+            let line = match self.executed.get(&address) {
+                Some((code, width)) => {
+                    let cells: Vec<String> = (address..address + width)
+                        .map(|cell| program.get(&cell).unwrap_or(&0).to_string())
+                        .collect();
+                    let name = instruction_set
+                        .get(*code)
+                        .map_or("?", |instruction| instruction.name());
+                    let mut line = format!(
+                        "{:>6} + {} {:<5} {}",
+                        address,
+                        access(address..address + width),
+                        name,
+                        cells.join(",")
+                    );
+                    if let Some(branch) = self.branches.get(&address) {
+                        line.push_str(&format!(
+                            "  [taken {}, not taken {}]",
+                            branch.taken, branch.not_taken
+                        ));
+                    }
+                    address += width;
+                    line
+                }
+                None => {
+                    let line = format!(
+                        "{:>6} - {} {:<5} {}",
+                        address,
+                        access(address..address + 1),
+                        "",
+                        program.get(&address).unwrap_or(&0)
+                    );
+                    address += 1;
+                    line
+                }
+            };
+            lines.push(line.trim_end().to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::super::{parse_mem, Computer, HaltedState, Instruction};
+    use super::*;
+
+    #[test]
+    fn test_coverage() {
+        // outputs 1 if the input is zero, otherwise 2
+        let program = "3,13,1006,13,9,104,2,99,0,104,1,99,0,0";
+        let mem = parse_mem(program.as_bytes()).unwrap();
+
+        let mut computer = Computer::new(&mem);
+        let coverage = Rc::new(RefCell::new(Coverage::new(computer.instruction_set())));
+        computer.set_hook(Box::new(Rc::clone(&coverage)));
+
+        assert_eq!(computer.run().unwrap(), HaltedState::Input);
+        computer.send_input(0).unwrap();
+        assert_eq!(computer.run().unwrap(), HaltedState::Output(1));
+        assert_eq!(computer.run().unwrap(), HaltedState::Halt);
+
+        let coverage = coverage.borrow();
+        assert!(coverage.is_executed(2));
+        assert!(!coverage.is_executed(5));
+        assert_eq!(
+            coverage.branch(2),
+            Some(&Branch {
+                taken: 1,
+                not_taken: 0
+            })
+        );
+
+        let summary = coverage.summary(&mem);
+        assert_eq!(
+            summary,
+            Summary {
+                program_cells: 14,
+                executed_cells: 8,
+                branch_directions: 2,
+                covered_directions: 1,
+                read_cells: 1,
+                written_cells: 1,
+            }
+        );
+
+        let listing = coverage.listing(&mem, computer.instruction_set());
+        let lines: Vec<&str> = listing.lines().collect();
+        assert_eq!(lines[0], "     0 +    in    3,13");
+        assert_eq!(
+            lines[1],
+            "     2 +    jf    1006,13,9  [taken 1, not taken 0]"
+        );
+        assert_eq!(lines[2], "     5 -          104");
+        assert_eq!(lines.last(), Some(&"    13 - rw       0"));
+    }
+
+    #[test]
+    fn test_custom_jumps() {
+        // jump if true moved to opcode 15, opcode 5 reused for a plain output
+        let mut instruction_set = InstructionSet::default();
+        let jump = instruction_set.get(5).unwrap().clone();
+        instruction_set.register(15, jump).unwrap();
+        let output = instruction_set.get(4).unwrap().clone();
+        instruction_set.register(5, output).unwrap();
+        let instruction_set = std::sync::Arc::new(instruction_set);
+
+        let mem = parse_mem("1115,1,4,99,105,1,99".as_bytes()).unwrap();
+        let mut computer = Computer::with_instruction_set(&mem, instruction_set);
+        let coverage = Rc::new(RefCell::new(Coverage::new(computer.instruction_set())));
+        computer.set_hook(Box::new(Rc::clone(&coverage)));
+
+        assert_eq!(computer.run().unwrap(), HaltedState::Output(1));
+        assert_eq!(computer.run().unwrap(), HaltedState::Halt);
+
+        let coverage = coverage.borrow();
+        assert_eq!(
+            coverage.branch(0),
+            Some(&Branch {
+                taken: 1,
+                not_taken: 0
+            })
+        );
+        assert_eq!(coverage.branch(4), None);
+    }
+
+    #[test]
+    fn test_renamed_jumps() {
+        // opcode 5 replaced by a halt that reuses the name of jump if true
+        let mut instruction_set = InstructionSet::default();
+        let halt = instruction_set.get(99).unwrap().clone();
+        let halt = Instruction::new(JUMP_IF_TRUE, &[], move |ctx, args| halt.execute(ctx, args));
+        instruction_set.register(5, halt).unwrap();
+        let instruction_set = std::sync::Arc::new(instruction_set);
+
+        let mem = parse_mem("104,1,5".as_bytes()).unwrap();
+        let mut computer = Computer::with_instruction_set(&mem, instruction_set);
+        let coverage = Rc::new(RefCell::new(Coverage::new(computer.instruction_set())));
+        computer.set_hook(Box::new(Rc::clone(&coverage)));
+
+        assert_eq!(computer.run().unwrap(), HaltedState::Output(1));
+        assert_eq!(computer.run().unwrap(), HaltedState::Halt);
+        assert!(coverage.borrow().is_executed(2));
+        assert_eq!(coverage.borrow().branch(2), None);
+    }
+}
@@ -0,0 +1,305 @@
+//! Differential testing of the Computer against a minimal reference interpreter
+//!
+//! The reference interpreter is written directly from the puzzle text and shares no code
+//! with the Computer, both are run side by side with the same inputs and step budget
+//! and must agree on outputs, final memory and how the run ended.
+//! Used by the fuzz targets in `fuzz/`.
+use std::collections::HashMap;
+
+use super::{Computer, HaltedState};
+
+/// How a run ended
+#[derive(Debug, PartialEq)]
+pub enum Ending {
+    Halt,
+    Error,
+    NeedsInput,
+    OutOfSteps,
+}
+
+/// Everything observable about a run
+#[derive(Debug, PartialEq)]
+pub struct Outcome {
+    pub outputs: Vec<isize>,
+    pub ram: HashMap<usize, isize>,
+    pub ending: Ending,
+    pub steps: usize,
+}
+
+/// Reference machine state, a `None` anywhere means the program faulted
+struct Reference {
+    ram: HashMap<usize, isize>,
+    ip: usize,
+    base: i128,
+}
+
+impl Reference {
+    fn cell(&self, address: usize) -> isize {
+        self.ram.get(&address).copied().unwrap_or(0)
+    }
+
+    /// Addresses are limited to the non-negative values of an isize
+    fn address(value: i128) -> Option<usize> {
+        if (0..=isize::MAX as i128).contains(&value) {
+            Some(value as usize)
+        } else {
+            None
+        }
+    }
+
+    fn mode(&self, index: u32) -> i128 {
+        self.cell(self.ip) as i128 / 10i128.pow(index + 2) % 10
+    }
+
+    fn raw(&self, index: u32) -> i128 {
+        self.cell(self.ip + 1 + index as usize) as i128
+    }
+
+    fn param(&self, index: u32) -> Option<isize> {
+        match self.mode(index) {
+            0 => Some(self.cell(Self::address(self.raw(index))?)),
+            1 => Some(self.raw(index) as isize),
+            2 => Some(self.cell(Self::address(self.base + self.raw(index))?)),
+            _ => None,
+        }
+    }
+
+    fn target(&self, index: u32) -> Option<usize> {
+        match self.mode(index) {
+            0 => Self::address(self.raw(index)),
+            2 => Self::address(self.base + self.raw(index)),
+            _ => None,
+        }
+    }
+
+    fn store(&mut self, address: usize, value: i128) -> Option<()> {
+        if value < isize::MIN as i128 || value > isize::MAX as i128 {
+            return None;
+        }
+        self.ram.insert(address, value as isize);
+        Some(())
+    }
+
+    /// Execute one instruction, returns an ending if the run is over
+    fn step(
+        &mut self,
+        inputs: &mut dyn Iterator<Item = isize>,
+        outputs: &mut Vec<isize>,
+    ) -> Option<Option<Ending>> {
+        let op = self.cell(self.ip);
+        if op < 0 {
+            return None;
+        }
+        match op % 100 {
+            code @ (1 | 2 | 7 | 8) => {
+                let (a, b) = (self.param(0)? as i128, self.param(1)? as i128);
+                let target = self.target(2)?;
+                let value = match code {
+                    1 => a + b,
+                    2 => a * b,
+                    7 => (a < b) as i128,
+                    _ => (a == b) as i128,
+                };
+                self.store(target, value)?;
+                self.ip += 4;
+            }
+            3 => {
+                let target = self.target(0)?;
+                match inputs.next() {
+                    Some(input) => self.store(target, input as i128)?,
+                    None => return Some(Some(Ending::NeedsInput)),
+                }
+                self.ip += 2;
+            }
+            4 => {
+                outputs.push(self.param(0)?);
+                self.ip += 2;
+            }
+            code @ (5 | 6) => {
+                let (value, target) = (self.param(0)?, self.param(1)?);
+                if (value != 0) == (code == 5) {
+                    self.ip = Self::address(target as i128)?;
+                } else {
+                    self.ip += 3;
+                }
+            }
+            9 => {
+                self.base += self.param(0)? as i128;
+                Self::address(self.base)?;
+                self.ip += 2;
+            }
+            99 => return Some(Some(Ending::Halt)),
+            _ => return None,
+        }
+        Some(None)
+    }
+}
+
+/// Run a program on the reference interpreter for at most `budget` instructions
+pub fn reference(mem: &HashMap<usize, isize>, inputs: &[isize], budget: usize) -> Outcome {
+    let mut machine = Reference {
+        ram: mem.clone(),
+        ip: 0,
+        base: 0,
+    };
+    let mut inputs = inputs.iter().copied();
+    let mut outputs = Vec::new();
+    let mut steps = 0;
+
+    let ending = loop {
+        if steps == budget {
+            break Ending::OutOfSteps;
+        }
+        steps += 1;
+        match machine.step(&mut inputs, &mut outputs) {
+            Some(None) => (),
+            Some(Some(ending)) => break ending,
+            None => break Ending::Error,
+        }
+    };
+
+    Outcome {
+        outputs,
+        ram: machine.ram,
+        ending,
+        steps,
+    }
+}
+
+/// Run a program on the Computer for at most `budget` instructions
+pub fn computer(mem: &HashMap<usize, isize>, inputs: &[isize], budget: usize) -> Outcome {
+    let mut computer = Computer::new(mem);
+    let mut inputs = inputs.iter();
+    let mut outputs = Vec::new();
+
+    let ending = loop {
+        if computer.instruction_count() == budget {
+            break Ending::OutOfSteps;
+        }
+        match computer.step() {
+            Ok(None) => (),
+            Ok(Some(HaltedState::Output(output))) => outputs.push(output),
+            Ok(Some(HaltedState::Input)) => match inputs.next() {
+                Some(input) => {
+                    if computer.send_input(*input).is_err() {
+                        break Ending::Error;
+                    }
+                }
+                None => break Ending::NeedsInput,
+            },
+            Ok(Some(HaltedState::Halt)) => break Ending::Halt,
+            Err(_) => break Ending::Error,
+        }
+    };
+
+    Outcome {
+        outputs,
+        steps: computer.instruction_count(),
+        ram: computer.ram,
+        ending,
+    }
+}
+
+/// Run a program on both interpreters, failing with both outcomes if they disagree
+pub fn compare(
+    mem: &HashMap<usize, isize>,
+    inputs: &[isize],
+    budget: usize,
+) -> Result<Outcome, String> {
+    let expected = reference(mem, inputs, budget);
+    let actual = computer(mem, inputs, budget);
+    if actual != expected {
+        return Err(format!(
+            "Computer disagrees with the reference interpreter\nComputer:  {:?}\nReference: {:?}",
+            actual, expected
+        ));
+    }
+    Ok(actual)
+}
+
+/// Build a program and its inputs from arbitrary bytes
+/// values are biased towards valid instructions and small addresses so runs get somewhere
+pub fn generate(bytes: &[u8]) -> (HashMap<usize, isize>, Vec<isize>) {
+    const CODES: [isize; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 99];
+    let mut bytes = bytes.iter().copied();
+    let mut next = move || bytes.next();
+
+    let small = |byte: u8| byte as isize / 4 - 8;
+    let inputs = (0..next().unwrap_or(0) % 8)
+        .map(|_| small(next().unwrap_or(0)))
+        .collect();
+
+    let mut program = Vec::new();
+    while let Some(kind) = next() {
+        let byte = next().unwrap_or(0);
+        let value = match kind % 8 {
+            // instruction with a mode digit per parameter, big values cover invalid modes
+            0..=4 => {
+                let modes = (0..3).fold(0, |modes, digit| {
+                    modes * 10 + [0, 0, 1, 2][(byte >> (digit * 2) & 3) as usize]
+                });
+                CODES[kind as usize / 8 % CODES.len()] + modes * 100
+            }
+            5 | 6 => small(byte),
+            _ => {
+                let mut value = byte as i64;
+                for _ in 0..7 {
+                    value = value << 8 | next().unwrap_or(0) as i64;
+                }
+                value as isize
+            }
+        };
+        program.push(value);
+    }
+
+    (program.into_iter().enumerate().collect(), inputs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::parse_mem;
+    use super::*;
+
+    #[test]
+    fn test_known_programs() {
+        // outputs the sum of its inputs, then faults on a negative jump
+        let mem = parse_mem("3,20,3,21,1,20,21,20,4,20,1105,1,-1".as_bytes()).unwrap();
+        let outcome = compare(&mem, &[4, 5], 100).unwrap();
+        assert_eq!(outcome.outputs, vec![9]);
+        assert_eq!(outcome.ending, Ending::Error);
+        assert_eq!(compare(&mem, &[4], 100).unwrap().ending, Ending::NeedsInput);
+
+        // multiplying out of range faults instead of wrapping
+        let mem = parse_mem("1102,4611686018427387904,2,0,99".as_bytes()).unwrap();
+        assert_eq!(compare(&mem, &[], 100).unwrap().ending, Ending::Error);
+
+        // moving the relative base below zero faults
+        let mem = parse_mem("109,-1,99".as_bytes()).unwrap();
+        assert_eq!(compare(&mem, &[], 100).unwrap().ending, Ending::Error);
+
+        // an endless loop stops at the budget
+        let mem = parse_mem("1105,1,0".as_bytes()).unwrap();
+        let outcome = compare(&mem, &[], 50).unwrap();
+        assert_eq!((outcome.ending, outcome.steps), (Ending::OutOfSteps, 50));
+    }
+
+    #[test]
+    fn test_generated_programs() {
+        // xorshift, so failures are reproducible
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..2000 {
+            let bytes: Vec<u8> = (0..64)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect();
+            let (mem, inputs) = generate(&bytes);
+            if let Err(e) = compare(&mem, &inputs, 500) {
+                panic!("{:?}\n{}", bytes, e);
+            }
+        }
+    }
+}
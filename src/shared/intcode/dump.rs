@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+use super::{Computer, InstructionSet, Parameter};
+
+/// Format an operand using its parameter mode
+/// Position Mode is shown as `[address]` and Relative Mode as `[rb+offset]`
+fn operand(mode: isize, value: isize) -> Option<String> {
+    match mode {
+        0 => Some(format!("[{}]", value)),
+        1 => Some(value.to_string()),
+        2 if value < 0 => Some(format!("[rb{}]", value)),
+        2 => Some(format!("[rb+{}]", value)),
+        _ => None,
+    }
+}
+
+/// Decode the instruction at an address along with its width
+/// cells that are not a valid instruction are treated as data
+fn decode(
+    mem: &HashMap<usize, isize>,
+    address: usize,
+    instruction_set: &InstructionSet,
+) -> Option<(String, usize)> {
+    let cell = |address: usize| *mem.get(&address).unwrap_or(&0);
+    let value = cell(address);
+    if value < 0 {
+        return None;
+    }
+    let instruction = instruction_set.get(value as usize % 100)?;
+
+    let mut modes = value / 100;
+    let mut operands = Vec::new();
+    for (index, parameter) in instruction.parameters().iter().enumerate() {
+        let mode = modes % 10;
+        modes /= 10;
+        if mode == 1 && *parameter == Parameter::Write {
+            return None;
+        }
+        operands.push(operand(mode, cell(address + 1 + index))?);
+    }
+
+    let text = format!("{} {}", instruction.name(), operands.join(", "));
+    Some((text.trim_end().to_string(), instruction.width()))
+}
+
+/// Start of each row of `columns` cells holding a set cell, in address order
+/// runs of rows without any set cells are given as `None`, only the populated addresses are
+/// visited so sparse memory with very high addresses is cheap to lay out
+fn rows(mem: &HashMap<usize, isize>, range: &Range<usize>, columns: usize) -> Vec<Option<usize>> {
+    let mut addresses: Vec<usize> = mem
+        .keys()
+        .copied()
+        .filter(|address| range.contains(address))
+        .map(|address| address - (address - range.start) % columns)
+        .collect();
+    addresses.sort_unstable();
+    addresses.dedup();
+
+    let mut rows = Vec::new();
+    let mut next_row = range.start;
+    for row in addresses {
+        if row > next_row {
+            rows.push(None);
+        }
+        rows.push(Some(row));
+        next_row = row + columns;
+    }
+    if next_row < range.end {
+        rows.push(None);
+    }
+    rows
+}
+
+/// Width of the widest set value in a range once formatted
+fn value_width<F>(mem: &HashMap<usize, isize>, range: &Range<usize>, format: F) -> usize
+where
+    F: Fn(isize) -> String,
+{
+    mem.iter()
+        .filter(|(address, _)| range.contains(address))
+        .map(|(_, value)| format(*value).len())
+        .max()
+        .unwrap_or(1)
+}
+
+/// Memory laid out in rows of `columns` cells, unset cells are shown as `.`
+/// each row is annotated with the instructions starting in it, found by decoding from the
+/// start of the range, rows without any set cells are collapsed into a single `*`
+pub fn dump(
+    mem: &HashMap<usize, isize>,
+    range: Range<usize>,
+    columns: usize,
+    instruction_set: &InstructionSet,
+) -> String {
+    let columns = columns.max(1);
+    let width = value_width(mem, &range, |value| value.to_string());
+
+    let mut lines = Vec::new();
+    let mut next_instruction = range.start;
+    for row in rows(mem, &range, columns) {
+        let row = match row {
+            Some(row) => row,
+            None => {
+                lines.push("*".to_string());
+                continue;
+            }
+        };
+        next_instruction = next_instruction.max(row);
+
+        let mut values = Vec::new();
+        let mut annotations = Vec::new();
+        for address in row..(row + columns).min(range.end) {
+            values.push(match mem.get(&address) {
+                Some(value) => format!("{:>width$}", value, width = width),
+                None => format!("{:>width$}", ".", width = width),
+            });
+            if address < next_instruction {
+                continue;
+            }
+            next_instruction = match decode(mem, address, instruction_set) {
+                Some((text, width)) => {
+                    annotations.push(text);
+                    address + width
+                }
+                None => address + 1,
+            };
+        }
+
+        let mut line = format!("{:>6}: {}", row, values.join(" "));
+        if !annotations.is_empty() {
+            line.push_str(&format!(" | {}", annotations.join("; ")));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Value in hexadecimal, negative values keep their sign
+fn hex(value: isize) -> String {
+    if value < 0 {
+        format!("-{:x}", value.unsigned_abs())
+    } else {
+        format!("{:x}", value)
+    }
+}
+
+/// Memory laid out like `dump` with addresses and values in hexadecimal
+/// each row ends with the cells read as ASCII, non printable cells are shown as `.`
+/// and unset cells as a space
+pub fn hex_dump(mem: &HashMap<usize, isize>, range: Range<usize>, columns: usize) -> String {
+    let columns = columns.max(1);
+    let width = value_width(mem, &range, hex);
+
+    let mut lines = Vec::new();
+    for row in rows(mem, &range, columns) {
+        let row = match row {
+            Some(row) => row,
+            None => {
+                lines.push("*".to_string());
+                continue;
+            }
+        };
+
+        let mut values = Vec::new();
+        let mut text = String::new();
+        for address in row..(row + columns).min(range.end) {
+            match mem.get(&address) {
+                Some(value) => {
+                    values.push(format!("{:>width$}", hex(*value), width = width));
+                    text.push(match *value {
+                        0x20..=0x7e => *value as u8 as char,
+                        _ => '.',
+                    });
+                }
+                None => {
+                    values.push(format!("{:>width$}", ".", width = width));
+                    text.push(' ');
+                }
+            }
+        }
+        lines.push(format!("{:08x}: {} |{}|", row, values.join(" "), text));
+    }
+    lines.join("\n")
+}
+
+/// A memory cell whose value differs between two snapshots
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub address: usize,
+    pub before: isize,
+    pub after: isize,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>6}: {} -> {}", self.address, self.before, self.after)
+    }
+}
+
+/// Cells that differ between two snapshots in address order, unset cells count as 0
+pub fn diff(before: &HashMap<usize, isize>, after: &HashMap<usize, isize>) -> Vec<Change> {
+    let mut addresses: Vec<&usize> = before.keys().chain(after.keys()).collect();
+    addresses.sort();
+    addresses.dedup();
+
+    addresses
+        .into_iter()
+        .map(|address| Change {
+            address: *address,
+            before: *before.get(address).unwrap_or(&0),
+            after: *after.get(address).unwrap_or(&0),
+        })
+        .filter(|change| change.before != change.after)
+        .collect()
+}
+
+impl<'a> Computer<'a> {
+    /// Dump of the Computer's memory, see `dump`
+    pub fn dump(&self, range: Range<usize>, columns: usize) -> String {
+        dump(&self.ram, range, columns, &self.instruction_set)
+    }
+
+    /// Hexadecimal dump of the Computer's memory, see `hex_dump`
+    pub fn hex_dump(&self, range: Range<usize>, columns: usize) -> String {
+        hex_dump(&self.ram, range, columns)
+    }
+
+    /// Cells changed since the Computer was loaded
+    pub fn diff_rom(&self) -> Vec<Change> {
+        diff(self.rom, &self.ram)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{instructions, parse_mem, HaltedState};
+    use super::*;
+
+    #[test]
+    fn test_dump() {
+        let mut mem = parse_mem("1002,4,3,4,33,109,-2,204,1,99,-7".as_bytes()).unwrap();
+        mem.insert(40, 77);
+
+        let expected = [
+            "     0: 1002    4    3    4 | mul [4], 3, [4]",
+            "     4:   33  109   -2  204 | arb -2; out [rb+1]",
+            "     8:    1   99   -7    . | halt",
+            "*",
+            "    40:   77    .    .    .",
+        ];
+        assert_eq!(
+            dump(&mem, 0..44, 4, &instructions::standard()),
+            expected.join("\n")
+        );
+    }
+
+    #[test]
+    fn test_sparse_dump() {
+        // rows are found from the set cells rather than walked up to the highest address
+        let mut mem = parse_mem("104,7,99".as_bytes()).unwrap();
+        mem.insert(1_000_000_000_000, -1);
+
+        let expected = [
+            "     0: 104   7  99   . | out 7; halt",
+            "*",
+            "1000000000000:  -1   .   .   .",
+        ];
+        assert_eq!(
+            dump(&mem, 0..1_000_000_000_004, 4, &instructions::standard()),
+            expected.join("\n")
+        );
+        assert_eq!(
+            dump(&mem, 2..6, 4, &instructions::standard()),
+            "     2: 99  .  .  . | halt"
+        );
+        assert_eq!(
+            dump(&HashMap::new(), 0..8, 4, &instructions::standard()),
+            "*"
+        );
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let mut mem = parse_mem("104,72,104,105,-31,99".as_bytes()).unwrap();
+        mem.insert(48, 10);
+
+        let expected = [
+            "00000000:  68  48  68  69 |hHhi|",
+            "00000004: -1f  63   .   . |.c  |",
+            "*",
+            "00000030:   a   .   .   . |.   |",
+            "*",
+        ];
+        assert_eq!(hex_dump(&mem, 0..64, 4), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_diff() {
+        let mem = parse_mem("1002,4,3,4,33".as_bytes()).unwrap();
+        let mut computer = Computer::new(&mem);
+        assert!(computer.diff_rom().is_empty());
+
+        assert_eq!(computer.run().unwrap(), HaltedState::Halt);
+        assert_eq!(
+            computer.diff_rom(),
+            vec![Change {
+                address: 4,
+                before: 33,
+                after: 99
+            }]
+        );
+
+        let mut after = mem.clone();
+        after.remove(&0);
+        after.insert(10, 1);
+        let changes: Vec<String> = diff(&mem, &after).iter().map(|c| c.to_string()).collect();
+        assert_eq!(changes, vec!["     0: 1002 -> 0", "    10: 0 -> 1"]);
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::VecDeque;
+
+use super::Computer;
+
+/// State needed to undo a single instruction
+struct Step {
+    main_pointer: usize,
+    relative_pointer: usize,
+    instruction_pointer: usize,
+    instructions: usize,
+    /// addresses written along with their previous values, in write order
+    writes: Vec<(usize, Option<isize>)>,
+}
+
+/// Bounded undo log of executed instructions
+/// once full the oldest instructions are forgotten
+pub struct History {
+    capacity: usize,
+    steps: VecDeque<Step>,
+}
+
+impl History {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            steps: VecDeque::with_capacity(capacity.min(1024)),
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.steps.clear();
+    }
+
+    pub(super) fn record_write(&mut self, address: usize, previous: Option<isize>) {
+        if let Some(step) = self.steps.back_mut() {
+            step.writes.push((address, previous));
+        }
+    }
+}
+
+/// Instruction that last wrote to a memory cell
+#[derive(Debug, PartialEq)]
+pub struct Writer {
+    /// address of the writing instruction
+    pub instruction_pointer: usize,
+    /// instruction count at which the write happened
+    pub instruction_count: usize,
+}
+
+impl<'a> Computer<'a> {
+    /// Start recording an undo log of up to `capacity` instructions
+    /// any existing history is discarded
+    pub fn enable_history(&mut self, capacity: usize) {
+        self.history = Some(History::new(capacity));
+    }
+
+    /// Stop recording the undo log
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    /// Number of instructions that can currently be stepped back
+    pub fn history_len(&self) -> usize {
+        self.history
+            .as_ref()
+            .map_or(0, |history| history.steps.len())
+    }
+
+    /// Record the state before an instruction is read
+    pub(super) fn begin_step(&mut self) {
+        if let Some(history) = &mut self.history {
+            if history.capacity == 0 {
+                return;
+            }
+            if history.steps.len() == history.capacity {
+                history.steps.pop_front();
+            }
+            history.steps.push_back(Step {
+                main_pointer: self.main_pointer,
+                relative_pointer: self.relative_pointer,
+                instruction_pointer: self.instruction_pointer,
+                instructions: self.instructions,
+                writes: Vec::new(),
+            });
+        }
+    }
+
+    /// Undo the most recent instruction, returns false once the history is exhausted
+    pub fn step_back(&mut self) -> bool {
+        let step = match self
+            .history
+            .as_mut()
+            .and_then(|history| history.steps.pop_back())
+        {
+            Some(step) => step,
+            None => return false,
+        };
+
+        for (address, previous) in step.writes.into_iter().rev() {
+            let undone = match previous {
+                Some(value) => self.ram.insert(address, value),
+                None => self.ram.remove(&address),
+            };
+            if let Some(detector) = &mut self.loop_detector {
+                detector.record_write(address, undone.unwrap_or(0), previous.unwrap_or(0));
+            }
+        }
+        if let Some(detector) = &mut self.loop_detector {
+            detector.clear();
+        }
+        self.main_pointer = step.main_pointer;
+        self.relative_pointer = step.relative_pointer;
+        self.instruction_pointer = step.instruction_pointer;
+        self.instructions = step.instructions;
+        self.w_ptr = None;
+        true
+    }
+
+    /// Step back until just before the most recent write to `address`
+    /// returns the address of the writing instruction or None if it is not in the history
+    pub fn run_back_to_write(&mut self, address: usize) -> Option<usize> {
+        loop {
+            let wrote = match self
+                .history
+                .as_ref()
+                .and_then(|history| history.steps.back())
+            {
+                Some(step) => step.writes.iter().any(|(written, _)| *written == address),
+                None => return None,
+            };
+            self.step_back();
+            if wrote {
+                return Some(self.main_pointer);
+            }
+        }
+    }
+
+    /// Find the instruction that last wrote to `address` within the history
+    pub fn last_writer(&self, address: usize) -> Option<Writer> {
+        self.history
+            .as_ref()?
+            .steps
+            .iter()
+            .rev()
+            .find(|step| step.writes.iter().any(|(written, _)| *written == address))
+            .map(|step| Writer {
+                instruction_pointer: step.main_pointer,
+                instruction_count: step.instructions + 1,
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{parse_mem, HaltedState};
+    use super::*;
+
+    /// reads an input, doubles it into cell 20 three times then outputs it
+    const PROGRAM: &str = "3,20,1,20,20,20,1,20,20,20,1,20,20,20,4,20,99";
+
+    #[test]
+    fn test_step_back() {
+        let mem = parse_mem(PROGRAM.as_bytes()).unwrap();
+        let mut computer = Computer::new(&mem);
+        computer.enable_history(100);
+
+        assert!(matches!(computer.run().unwrap(), HaltedState::Input));
+        computer.send_input(3).unwrap();
+        assert!(matches!(computer.run().unwrap(), HaltedState::Output(24)));
+        assert_eq!(computer.history_len(), 5);
+
+        assert!(computer.step_back());
+        assert_eq!(computer.ram[&20], 24);
+        assert_eq!(computer.main_pointer(), 14);
+
+        assert!(computer.step_back());
+        assert_eq!(computer.ram[&20], 12);
+        assert_eq!(computer.main_pointer(), 10);
+        assert_eq!(computer.instruction_count(), 3);
+
+        assert!(computer.step_back());
+        assert!(computer.step_back());
+        assert!(computer.step_back());
+        assert_eq!(computer.ram, mem);
+        assert_eq!(computer.main_pointer(), 0);
+        assert_eq!(computer.instruction_count(), 0);
+        assert!(!computer.step_back());
+
+        // replaying forward gives the same result
+        assert!(matches!(computer.run().unwrap(), HaltedState::Input));
+        computer.send_input(3).unwrap();
+        assert!(matches!(computer.run().unwrap(), HaltedState::Output(24)));
+    }
+
+    #[test]
+    fn test_run_back_to_write() {
+        let mem = parse_mem(PROGRAM.as_bytes()).unwrap();
+        let mut computer = Computer::new(&mem);
+        computer.enable_history(100);
+
+        computer.run().unwrap();
+        computer.send_input(3).unwrap();
+        computer.run().unwrap();
+
+        let writer = computer.last_writer(20).unwrap();
+        assert_eq!(writer.instruction_pointer, 10);
+        assert_eq!(writer.instruction_count, 4);
+        assert_eq!(computer.last_writer(0), None);
+
+        assert_eq!(computer.run_back_to_write(20), Some(10));
+        assert_eq!(computer.ram[&20], 12);
+        assert_eq!(computer.run_back_to_write(20), Some(6));
+        assert_eq!(computer.run_back_to_write(20), Some(2));
+        assert_eq!(computer.run_back_to_write(20), Some(0));
+        assert_eq!(computer.ram.get(&20), None);
+        assert_eq!(computer.run_back_to_write(20), None);
+    }
+
+    #[test]
+    fn test_history_capacity() {
+        let mem = parse_mem(PROGRAM.as_bytes()).unwrap();
+        let mut computer = Computer::new(&mem);
+        computer.enable_history(2);
+
+        computer.run().unwrap();
+        computer.send_input(3).unwrap();
+        computer.run().unwrap();
+        assert_eq!(computer.history_len(), 2);
+
+        assert!(computer.step_back());
+        assert!(computer.step_back());
+        assert!(!computer.step_back());
+        assert_eq!(computer.main_pointer(), 10);
+        assert_eq!(computer.ram[&20], 12);
+    }
+}
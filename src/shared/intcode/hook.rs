@@ -0,0 +1,31 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Observer of a Computer's execution
+/// all methods default to doing nothing so hooks only implement what they need
+pub trait Hook {
+    /// Called after an instruction executes
+    /// `args` are the decoded parameters and `next` is the address of the next instruction
+    fn instruction(&mut self, _address: usize, _code: usize, _args: &[isize], _next: usize) {}
+
+    /// Called when an instruction reads a memory cell
+    fn read(&mut self, _address: usize) {}
+
+    /// Called when a memory cell is written
+    fn write(&mut self, _address: usize, _value: isize) {}
+}
+
+/// Shared hooks let the caller keep a handle to inspect results
+impl<H: Hook> Hook for Rc<RefCell<H>> {
+    fn instruction(&mut self, address: usize, code: usize, args: &[isize], next: usize) {
+        self.borrow_mut().instruction(address, code, args, next);
+    }
+
+    fn read(&mut self, address: usize) {
+        self.borrow_mut().read(address);
+    }
+
+    fn write(&mut self, address: usize, value: isize) {
+        self.borrow_mut().write(address, value);
+    }
+}
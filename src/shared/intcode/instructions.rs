@@ -0,0 +1,338 @@
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+use super::{Computer, HaltedState};
+
+/// Opcodes are the last two digits of an instruction
+const MAX_OPCODES: usize = 100;
+
+/// How an instruction uses one of its parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Parameter {
+    /// value is read using the parameter mode
+    Read,
+    /// value is the address to write to, Immediate Mode is not allowed
+    Write,
+}
+
+/// Access to the machine while an instruction executes
+pub struct Context<'c, 'a> {
+    computer: &'c mut Computer<'a>,
+}
+
+impl<'c, 'a> Context<'c, 'a> {
+    pub(super) fn new(computer: &'c mut Computer<'a>) -> Self {
+        Self { computer }
+    }
+
+    /// Read value at an address
+    pub fn read(&mut self, address: usize) -> isize {
+        self.computer.read(address)
+    }
+
+    /// Write value to an address
+    pub fn write(&mut self, address: usize, value: isize) {
+        self.computer.write_to(address, value);
+    }
+
+    /// Move the main pointer to a new address
+    pub fn jump(&mut self, address: usize) {
+        self.computer.main_pointer = address;
+    }
+
+    /// Current position of the main pointer, this is the start of the next instruction
+    pub fn main_pointer(&self) -> usize {
+        self.computer.main_pointer
+    }
+
+    /// Current position of the relative base pointer
+    pub fn relative_pointer(&self) -> usize {
+        self.computer.relative_pointer
+    }
+
+    /// Move the relative base pointer by an offset, the pointer must not become negative
+    pub fn adjust_relative_pointer(&mut self, offset: isize) -> Result<(), String> {
+        self.computer.relative_pointer = self.computer.relative_address(offset)?;
+        Ok(())
+    }
+
+    /// Set the address the next input will be written to
+    /// the instruction should then halt with `HaltedState::Input`
+    pub fn await_input(&mut self, address: usize) {
+        self.computer.w_ptr = Some(address);
+    }
+}
+
+/// Convert a value to a memory address, addresses must not be negative
+pub fn address(value: isize) -> Result<usize, String> {
+    if value < 0 {
+        return Err(format!("Negative address {}", value));
+    }
+    Ok(value as usize)
+}
+
+/// Wrap the result of checked arithmetic
+fn checked(value: Option<isize>) -> Result<isize, String> {
+    value.ok_or_else(|| "Arithmetic overflow".to_string())
+}
+
+type Execute = dyn Fn(&mut Context, &[isize]) -> Result<Option<HaltedState>, String> + Send + Sync;
+
+/// Definition of a single instruction
+#[derive(Clone)]
+pub struct Instruction {
+    name: String,
+    parameters: Vec<Parameter>,
+    execute: Arc<Execute>,
+}
+
+impl Instruction {
+    /// Create an instruction, `execute` receives a value for each parameter
+    /// read parameters hold the value read and write parameters hold the address to write to
+    /// returning a halted state stops the Computer's run
+    pub fn new<F>(name: &str, parameters: &[Parameter], execute: F) -> Self
+    where
+        F: Fn(&mut Context, &[isize]) -> Result<Option<HaltedState>, String>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            parameters: parameters.to_vec(),
+            execute: Arc::new(execute),
+        }
+    }
+
+    /// Mnemonic of the instruction
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Parameters of the instruction, in order
+    pub fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    /// Number of memory cells taken up by the instruction
+    pub fn width(&self) -> usize {
+        self.parameters.len() + 1
+    }
+
+    pub(super) fn execute(
+        &self,
+        context: &mut Context,
+        args: &[isize],
+    ) -> Result<Option<HaltedState>, String> {
+        (self.execute)(context, args)
+    }
+}
+
+impl fmt::Debug for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Instruction")
+            .field("name", &self.name)
+            .field("parameters", &self.parameters)
+            .finish()
+    }
+}
+
+/// Table of instructions a Computer understands, indexed by opcode
+#[derive(Clone, Debug)]
+pub struct InstructionSet {
+    table: Vec<Option<Instruction>>,
+}
+
+impl InstructionSet {
+    /// Instruction set without any instructions
+    pub fn empty() -> Self {
+        Self {
+            table: vec![None; MAX_OPCODES],
+        }
+    }
+
+    /// Register an instruction to an opcode, replacing any existing instruction
+    pub fn register(&mut self, code: usize, instruction: Instruction) -> Result<(), String> {
+        match self.table.get_mut(code) {
+            Some(entry) => {
+                *entry = Some(instruction);
+                Ok(())
+            }
+            None => Err(format!(
+                "Opcode {} is out of range, opcodes must be less than {}",
+                code, MAX_OPCODES
+            )),
+        }
+    }
+
+    /// Look up the instruction for an opcode
+    pub fn get(&self, code: usize) -> Option<&Instruction> {
+        self.table.get(code)?.as_ref()
+    }
+
+    /// Look up the opcode of an instruction by name
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.table
+            .iter()
+            .position(|entry| matches!(entry, Some(i) if i.name() == name))
+    }
+}
+
+impl Default for InstructionSet {
+    /// Standard Intcode instruction set (opcodes 1-9 and 99)
+    fn default() -> Self {
+        use Parameter::{Read, Write};
+
+        let mut set = Self::empty();
+        let standard = vec![
+            (
+                1,
+                Instruction::new("add", &[Read, Read, Write], |ctx, args| {
+                    ctx.write(args[2] as usize, checked(args[0].checked_add(args[1]))?);
+                    Ok(None)
+                }),
+            ),
+            (
+                2,
+                Instruction::new("mul", &[Read, Read, Write], |ctx, args| {
+                    ctx.write(args[2] as usize, checked(args[0].checked_mul(args[1]))?);
+                    Ok(None)
+                }),
+            ),
+            (
+                3,
+                Instruction::new("in", &[Write], |ctx, args| {
+                    ctx.await_input(args[0] as usize);
+                    Ok(Some(HaltedState::Input))
+                }),
+            ),
+            (
+                4,
+                Instruction::new("out", &[Read], |_, args| {
+                    Ok(Some(HaltedState::Output(args[0])))
+                }),
+            ),
+            (
+                5,
+                Instruction::new("jt", &[Read, Read], |ctx, args| {
+                    if args[0] != 0 {
+                        ctx.jump(address(args[1])?);
+                    }
+                    Ok(None)
+                }),
+            ),
+            (
+                6,
+                Instruction::new("jf", &[Read, Read], |ctx, args| {
+                    if args[0] == 0 {
+                        ctx.jump(address(args[1])?);
+                    }
+                    Ok(None)
+                }),
+            ),
+            (
+                7,
+                Instruction::new("lt", &[Read, Read, Write], |ctx, args| {
+                    ctx.write(args[2] as usize, (args[0] < args[1]) as isize);
+                    Ok(None)
+                }),
+            ),
+            (
+                8,
+                Instruction::new("eq", &[Read, Read, Write], |ctx, args| {
+                    ctx.write(args[2] as usize, (args[0] == args[1]) as isize);
+                    Ok(None)
+                }),
+            ),
+            (
+                9,
+                Instruction::new("arb", &[Read], |ctx, args| {
+                    ctx.adjust_relative_pointer(args[0])?;
+                    Ok(None)
+                }),
+            ),
+            (
+                99,
+                Instruction::new("halt", &[], |_, _| Ok(Some(HaltedState::Halt))),
+            ),
+        ];
+
+        for (code, instruction) in standard {
+            set.register(code, instruction).unwrap();
+        }
+        set
+    }
+}
+
+/// Shared copy of the standard instruction set
+pub fn standard() -> Arc<InstructionSet> {
+    static STANDARD: OnceLock<Arc<InstructionSet>> = OnceLock::new();
+    Arc::clone(STANDARD.get_or_init(|| Arc::new(InstructionSet::default())))
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::parse_mem;
+    use super::*;
+
+    #[test]
+    fn test_register() {
+        let mut set = InstructionSet::empty();
+        assert!(set.get(1).is_none());
+
+        set.register(1, Instruction::new("nop", &[], |_, _| Ok(None)))
+            .unwrap();
+        assert_eq!(set.get(1).unwrap().name(), "nop");
+        assert!(set
+            .register(100, Instruction::new("bad", &[], |_, _| Ok(None)))
+            .is_err());
+
+        let standard = standard();
+        assert_eq!(standard.get(1).unwrap().width(), 4);
+        assert_eq!(standard.get(99).unwrap().width(), 1);
+        assert!(standard.get(10).is_none());
+    }
+
+    #[test]
+    fn test_custom_instructions() {
+        use Parameter::{Read, Write};
+
+        let mut set = InstructionSet::default();
+        set.register(
+            10,
+            Instruction::new("div", &[Read, Read, Write], |ctx, args| {
+                if args[1] == 0 {
+                    return Err("Division by zero".to_string());
+                }
+                ctx.write(args[2] as usize, args[0] / args[1]);
+                Ok(None)
+            }),
+        )
+        .unwrap();
+        set.register(
+            11,
+            Instruction::new("mod", &[Read, Read, Write], |ctx, args| {
+                ctx.write(args[2] as usize, args[0].rem_euclid(args[1]));
+                Ok(None)
+            }),
+        )
+        .unwrap();
+        let set = Arc::new(set);
+
+        // outputs 17 / 5 and 17 % 5
+        let mem = parse_mem("1110,17,5,20,1111,17,5,21,4,20,4,21,99".as_bytes()).unwrap();
+        let mut computer = Computer::with_instruction_set(&mem, Arc::clone(&set));
+        assert_eq!(computer.run().unwrap(), HaltedState::Output(3));
+        assert_eq!(computer.run().unwrap(), HaltedState::Output(2));
+        assert_eq!(computer.run().unwrap(), HaltedState::Halt);
+
+        let mem = parse_mem("1110,17,0,20,99".as_bytes()).unwrap();
+        let mut computer = Computer::with_instruction_set(&mem, Arc::clone(&set));
+        assert!(computer.run().is_err());
+
+        // custom opcodes are unknown to the standard set
+        let mem = parse_mem("1110,17,5,20,99".as_bytes()).unwrap();
+        let mut computer = Computer::new(&mem);
+        assert!(computer.run().is_err());
+    }
+}
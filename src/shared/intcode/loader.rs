@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::{error, fmt, io};
+
+/// Header identifying a binary memory image
+const IMAGE_MAGIC: &[u8] = b"INTC\x01";
+
+/// Error raised when a program token is unable to be parsed
+#[derive(Debug)]
+pub struct ParseError {
+    pub index: usize,
+    pub token: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unable to parse value #{} \"{}\": {}",
+            self.index, self.token, self.reason
+        )
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Parse comma separated program text
+/// whitespace around values is ignored and `#` starts a comment running to the end of the line
+pub fn parse_text(text: &str) -> Result<HashMap<usize, isize>, ParseError> {
+    let code = text
+        .lines()
+        .map(|line| match line.find('#') {
+            Some(comment) => &line[..comment],
+            None => line,
+        })
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    let mut tokens: Vec<&str> = code.split(',').map(|token| token.trim()).collect();
+    // allow a trailing comma (or an empty program)
+    if tokens.last() == Some(&"") {
+        tokens.pop();
+    }
+
+    let mut mem = HashMap::with_capacity(tokens.len());
+    for (index, token) in tokens.into_iter().enumerate() {
+        let value = token.parse().map_err(|e| ParseError {
+            index,
+            token: token.to_string(),
+            reason: format!("{}", e),
+        })?;
+        mem.insert(index, value);
+    }
+
+    Ok(mem)
+}
+
+fn write_varint<W>(writer: &mut W, mut value: u64) -> io::Result<()>
+where
+    W: io::Write,
+{
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint(bytes: &mut std::slice::Iter<u8>) -> Result<u64, String> {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = bytes.next().ok_or("Unexpected end of memory image")?;
+        if shift >= 64 {
+            return Err("Memory image value is too large".to_string());
+        }
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+/// Write memory as a compact binary image
+/// cells are stored in address order as varint address gaps and zigzag encoded values
+pub fn write_image<W>(mem: &HashMap<usize, isize>, mut writer: W) -> io::Result<()>
+where
+    W: io::Write,
+{
+    let mut addresses: Vec<&usize> = mem.keys().collect();
+    addresses.sort();
+
+    writer.write_all(IMAGE_MAGIC)?;
+    write_varint(&mut writer, addresses.len() as u64)?;
+
+    let mut next = 0;
+    for address in addresses {
+        let value = mem[address] as i64;
+        write_varint(&mut writer, (address - next) as u64)?;
+        write_varint(&mut writer, ((value << 1) ^ (value >> 63)) as u64)?;
+        next = address + 1;
+    }
+    writer.flush()
+}
+
+/// Read memory from a binary image created by `write_image`
+pub fn read_image(bytes: &[u8]) -> Result<HashMap<usize, isize>, String> {
+    if !bytes.starts_with(IMAGE_MAGIC) {
+        return Err("Not an Intcode memory image".to_string());
+    }
+    let mut bytes = bytes[IMAGE_MAGIC.len()..].iter();
+
+    let count = read_varint(&mut bytes)? as usize;
+    let mut mem = HashMap::with_capacity(count.min(bytes.len()));
+    let mut next = 0usize;
+    for _ in 0..count {
+        let address = next
+            .checked_add(read_varint(&mut bytes)? as usize)
+            .ok_or("Memory image address is out of range")?;
+        let value = read_varint(&mut bytes)?;
+        mem.insert(
+            address,
+            ((value >> 1) as i64 ^ -((value & 1) as i64)) as isize,
+        );
+        next = address.saturating_add(1);
+    }
+
+    if bytes.next().is_some() {
+        return Err("Unexpected data after memory image".to_string());
+    }
+    Ok(mem)
+}
+
+/// Helper function to parse memory input from file
+/// accepts either program text or a binary memory image
+pub fn parse_mem<T>(mut reader: T) -> Result<HashMap<usize, isize>, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    if bytes.starts_with(IMAGE_MAGIC) {
+        return Ok(read_image(&bytes)?);
+    }
+    Ok(parse_text(&String::from_utf8(bytes)?)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_whitespace() {
+        let expected = vec![1, -2, 3, 4].into_iter().enumerate().collect();
+
+        let actual = parse_text(" 1, -2,\r\n3 ,\n 4\r\n").unwrap();
+        assert_eq!(actual, expected);
+
+        let actual = parse_text("1,-2 # add\n# comment only\n,3,4,").unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_text("1,2,\n3x,4").unwrap_err();
+
+        assert_eq!(error.index, 2);
+        assert_eq!(error.token, "3x");
+
+        let error = parse_text("1,,2").unwrap_err();
+        assert_eq!(error.index, 1);
+        assert_eq!(error.token, "");
+    }
+
+    #[test]
+    fn test_image_round_trip() {
+        let mut mem: HashMap<usize, isize> = vec![1, -1, 0, isize::MAX, isize::MIN, 99]
+            .into_iter()
+            .enumerate()
+            .collect();
+        mem.insert(1000, 7);
+
+        let mut image = Vec::new();
+        write_image(&mem, &mut image).unwrap();
+
+        assert_eq!(read_image(&image).unwrap(), mem);
+        assert_eq!(parse_mem(image.as_slice()).unwrap(), mem);
+
+        image.pop();
+        assert!(read_image(&image).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::{error, fmt};
+
+use super::Computer;
+
+/// Mix the bits of a value (splitmix64 finalizer)
+fn mix(mut value: u64) -> u64 {
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+/// Hash contribution of a single memory cell, unset cells and zero cells are equal
+fn cell_hash(address: usize, value: isize) -> u64 {
+    if value == 0 {
+        return 0;
+    }
+    mix((address as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ mix(value as u64))
+}
+
+/// Machine state after a taken jump
+#[derive(PartialEq, Eq, Hash, Clone)]
+struct State {
+    main_pointer: usize,
+    relative_pointer: usize,
+    memory_hash: u64,
+}
+
+/// A revisited state waiting to be confirmed, along with the memory it was revisited with
+struct Candidate {
+    state: State,
+    count: usize,
+    ram: HashMap<usize, isize>,
+}
+
+/// Detects a program revisiting an earlier machine state without any I/O
+/// memory is tracked with an incrementally updated hash so unchanged memory costs nothing,
+/// a matching hash only marks a candidate, which is confirmed when the state comes around
+/// again with exactly the same memory
+pub struct LoopDetector {
+    capacity: usize,
+    memory_hash: u64,
+    seen: HashMap<State, usize>,
+    candidate: Option<Candidate>,
+}
+
+impl LoopDetector {
+    fn new(capacity: usize, ram: &HashMap<usize, isize>) -> Self {
+        Self {
+            capacity,
+            memory_hash: memory_hash(ram),
+            seen: HashMap::new(),
+            candidate: None,
+        }
+    }
+
+    pub(super) fn record_write(&mut self, address: usize, previous: isize, value: isize) {
+        self.memory_hash ^= cell_hash(address, previous) ^ cell_hash(address, value);
+    }
+
+    /// Forget the states seen so far, I/O makes revisiting a state no longer a loop
+    pub(super) fn clear(&mut self) {
+        self.seen.clear();
+        self.candidate = None;
+    }
+
+    /// Start over with memory replaced as a whole
+    pub(super) fn restart(&mut self, ram: &HashMap<usize, isize>) {
+        self.clear();
+        self.memory_hash = memory_hash(ram);
+    }
+}
+
+fn memory_hash(ram: &HashMap<usize, isize>) -> u64 {
+    ram.iter().fold(0, |hash, (address, value)| {
+        hash ^ cell_hash(*address, *value)
+    })
+}
+
+/// Compare memory where unset cells and zero cells are equal
+fn same_memory(a: &HashMap<usize, isize>, b: &HashMap<usize, isize>) -> bool {
+    let matches = |a: &HashMap<usize, isize>, b: &HashMap<usize, isize>| {
+        a.iter()
+            .all(|(address, value)| b.get(address).unwrap_or(&0) == value)
+    };
+    matches(a, b) && matches(b, a)
+}
+
+/// Raised when a run would never reach input, output or halt
+#[derive(Debug, PartialEq)]
+pub struct Looping {
+    /// instruction count at which the repeated state was caught, the program was already in the
+    /// cycle by then but may have entered it earlier
+    pub repeated_at: usize,
+    /// number of instructions in the cycle
+    pub length: usize,
+    /// address the cycle returns to
+    pub address: usize,
+}
+
+impl fmt::Display for Looping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Program is looping: cycle of {} instructions returning to address {}, repeating by instruction {}",
+            self.length, self.address, self.repeated_at
+        )
+    }
+}
+
+impl error::Error for Looping {}
+
+impl<'a> Computer<'a> {
+    /// Detect infinite loops, up to `capacity` machine states are remembered between I/O
+    /// once full the remembered states are discarded, so only cycles shorter than
+    /// `capacity` jumps are guaranteed to be found and may be caught late into the cycle
+    /// memory written directly through `ram` rather than `poke` is missed until the next `reset`
+    pub fn enable_loop_detection(&mut self, capacity: usize) {
+        self.loop_detector = Some(LoopDetector::new(capacity, &self.ram));
+    }
+
+    /// Stop detecting infinite loops
+    pub fn disable_loop_detection(&mut self) {
+        self.loop_detector = None;
+    }
+
+    /// Record the current state, failing once a revisited state is confirmed
+    pub(super) fn check_loop(&mut self) -> Result<(), Looping> {
+        let detector = match &mut self.loop_detector {
+            Some(detector) => detector,
+            None => return Ok(()),
+        };
+
+        let state = State {
+            main_pointer: self.main_pointer,
+            relative_pointer: self.relative_pointer,
+            memory_hash: detector.memory_hash,
+        };
+        if let Some(candidate) = &detector.candidate {
+            if candidate.state == state {
+                if same_memory(&candidate.ram, &self.ram) {
+                    return Err(Looping {
+                        repeated_at: candidate.count,
+                        length: self.instructions - candidate.count,
+                        address: self.main_pointer,
+                    });
+                }
+                // the hashes collided
+                detector.candidate = None;
+            }
+        }
+
+        if detector.candidate.is_none() && detector.seen.contains_key(&state) {
+            detector.candidate = Some(Candidate {
+                state: state.clone(),
+                count: self.instructions,
+                ram: self.ram.clone(),
+            });
+        }
+        if detector.seen.len() >= detector.capacity {
+            detector.seen.clear();
+        }
+        detector.seen.insert(state, self.instructions);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{parse_mem, HaltedState};
+    use super::*;
+
+    fn run_looping(program: &str) -> Looping {
+        let mem = parse_mem(program.as_bytes()).unwrap();
+        let mut computer = Computer::new(&mem);
+        computer.enable_loop_detection(1000);
+
+        let error = computer.run().unwrap_err();
+        match error.downcast::<Looping>() {
+            Ok(looping) => *looping,
+            Err(e) => panic!("Expected a loop but got: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_tight_loop() {
+        // the state after the first jump is repeated after the second, then confirmed after the third
+        assert_eq!(
+            run_looping("1105,1,0"),
+            Looping {
+                repeated_at: 2,
+                length: 1,
+                address: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_memory_loop() {
+        // toggles cell 20 between 1 and 0 forever, so the state after the first jump (instruction 2)
+        // comes around again with the same memory after the third (instruction 6), confirmed after the fifth
+        assert_eq!(
+            run_looping("8,20,21,20,1105,1,0"),
+            Looping {
+                repeated_at: 6,
+                length: 4,
+                address: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_no_loop() {
+        // counts down from 3 and outputs each value, then halts
+        let mut mem = parse_mem("4,20,1001,20,-1,20,1005,20,0,99".as_bytes()).unwrap();
+        mem.insert(20, 3);
+        let mut computer = Computer::new(&mem);
+        computer.enable_loop_detection(1000);
+
+        let mut outputs = Vec::new();
+        loop {
+            match computer.run().unwrap() {
+                HaltedState::Output(output) => outputs.push(output),
+                HaltedState::Halt => break,
+                HaltedState::Input => panic!("Input not expected!"),
+            }
+        }
+        assert_eq!(outputs, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_hash_collision() {
+        // jumps back to the start while cell 20 is set
+        let mut mem = parse_mem("1005,20,0,99".as_bytes()).unwrap();
+        mem.insert(20, 1);
+        let mut computer = Computer::new(&mem);
+        computer.enable_loop_detection(1000);
+
+        // the second visit only marks a candidate
+        assert_eq!(computer.step().unwrap(), None);
+        assert_eq!(computer.step().unwrap(), None);
+
+        // writing around the hash makes the next visit match on hash alone,
+        // which only replaces the candidate
+        computer.ram.insert(20, 2);
+        assert_eq!(computer.step().unwrap(), None);
+
+        let error = computer.step().unwrap_err();
+        assert_eq!(
+            *error.downcast::<Looping>().unwrap(),
+            Looping {
+                repeated_at: 3,
+                length: 1,
+                address: 0
+            }
+        );
+
+        computer.reset();
+        computer.poke(20, 0);
+        assert_eq!(computer.run().unwrap(), HaltedState::Halt);
+    }
+}
@@ -1,6 +1,26 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::{error, io};
 
+pub mod adapters;
+pub mod ascii;
+pub mod coverage;
+pub mod differential;
+pub mod dump;
+pub mod history;
+pub mod hook;
+pub mod instructions;
+pub mod loader;
+pub mod looping;
+pub mod session;
+
+#[cfg(test)]
+mod properties;
+
+pub use hook::Hook;
+pub use instructions::{Context, Instruction, InstructionSet, Parameter};
+pub use loader::parse_mem;
+
 enum ParameterMode {
     Position,
     Immediate,
@@ -41,21 +61,14 @@ impl Iterator for ParameterModeParser {
     }
 }
 
-enum OpCode {
-    Add { value_1: isize, value_2: isize },
-    Mul { value_1: isize, value_2: isize },
-    Input { input: Option<isize> },
-    Output { value_1: isize },
-    JumpIfTrue { value_1: isize, value_2: isize },
-    JumpIfFalse { value_1: isize, value_2: isize },
-    LessThan { value_1: isize, value_2: isize },
-    Equals { value_1: isize, value_2: isize },
-    UpdateRelativePointer { value_1: isize },
-    Halt,
+/// Decoded instruction, write parameters hold their resolved address
+struct OpCode {
+    code: usize,
+    args: Vec<isize>,
 }
 
 /// Halted States for calling code
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum HaltedState {
     Input,
     Output(isize),
@@ -71,222 +84,258 @@ pub struct Computer<'a> {
     relative_pointer: usize,
 
     w_ptr: Option<usize>,
+
+    instructions: usize,
+    instruction_pointer: usize,
+
+    history: Option<history::History>,
+    loop_detector: Option<looping::LoopDetector>,
+    hook: Option<Box<dyn Hook + 'a>>,
+
+    instruction_set: Arc<InstructionSet>,
 }
 
 impl<'a> Computer<'a> {
     /// Initialize a new Computer
     pub fn new(rom: &'a HashMap<usize, isize>) -> Computer<'a> {
+        Self::with_instruction_set(rom, instructions::standard())
+    }
+
+    /// Initialize a new Computer running a custom instruction set
+    pub fn with_instruction_set(
+        rom: &'a HashMap<usize, isize>,
+        instruction_set: Arc<InstructionSet>,
+    ) -> Computer<'a> {
         Computer {
             rom,
             ram: rom.clone(),
             main_pointer: 0,
             relative_pointer: 0,
             w_ptr: None,
+            instructions: 0,
+            instruction_pointer: 0,
+            history: None,
+            loop_detector: None,
+            hook: None,
+            instruction_set,
         }
     }
 
     /// Run Computer, returns a halted state for caller to act on.
     pub fn run(&mut self) -> Result<HaltedState, Box<dyn error::Error>> {
         loop {
-            let opcode = self.read_instruction()?;
-            if let Some(state) = self.execute_instruction(opcode) {
+            if let Some(state) = self.step()? {
                 return Ok(state);
             }
         }
     }
 
-    /// Reset the Computer State
+    /// Execute a single instruction, returns a halted state if the instruction produced one
+    pub fn step(&mut self) -> Result<Option<HaltedState>, Box<dyn error::Error>> {
+        self.begin_step();
+        self.instruction_pointer = self.main_pointer;
+        self.instructions += 1;
+        let opcode = self.read_instruction()?;
+        let next_instruction = self.main_pointer;
+        let hooked = self
+            .hook
+            .as_ref()
+            .map(|_| (opcode.code, opcode.args.clone()));
+
+        let state = self.execute_instruction(opcode)?;
+        if let (Some(hook), Some((code, args))) = (&mut self.hook, hooked) {
+            hook.instruction(self.instruction_pointer, code, &args, self.main_pointer);
+        }
+        if state.is_some() {
+            if let Some(detector) = &mut self.loop_detector {
+                detector.clear();
+            }
+            return Ok(state);
+        }
+        // a loop can only be closed by a jump
+        if self.main_pointer != next_instruction {
+            self.check_loop()?;
+        }
+        Ok(None)
+    }
+
+    /// Reset the Computer State, memory is copied back from the rom reusing its allocation
     pub fn reset(&mut self) {
-        self.ram = self.rom.clone();
+        self.ram.clone_from(self.rom);
         self.main_pointer = 0;
+        self.relative_pointer = 0;
+        self.w_ptr = None;
+        self.instructions = 0;
+        self.instruction_pointer = 0;
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+        if let Some(detector) = &mut self.loop_detector {
+            detector.restart(&self.ram);
+        }
+    }
+
+    /// Write to memory from outside the program, keeping loop detection up to date
+    pub fn poke(&mut self, address: usize, value: isize) {
+        let previous = self.ram.insert(address, value);
+        if let Some(detector) = &mut self.loop_detector {
+            detector.record_write(address, previous.unwrap_or(0), value);
+        }
+    }
+
+    /// Current position of the main pointer
+    pub fn main_pointer(&self) -> usize {
+        self.main_pointer
+    }
+
+    /// Current position of the relative base pointer
+    pub fn relative_pointer(&self) -> usize {
+        self.relative_pointer
+    }
+
+    /// Number of instructions executed since the Computer was started
+    pub fn instruction_count(&self) -> usize {
+        self.instructions
+    }
+
+    /// Instruction set the Computer is running
+    pub fn instruction_set(&self) -> &InstructionSet {
+        &self.instruction_set
+    }
+
+    /// Observe execution with a hook, replacing any existing hook
+    pub fn set_hook(&mut self, hook: Box<dyn Hook + 'a>) {
+        self.hook = Some(hook);
+    }
+
+    /// Remove the current hook
+    pub fn take_hook(&mut self) -> Option<Box<dyn Hook + 'a>> {
+        self.hook.take()
+    }
+
+    /// Value at an address without notifying the hook, unset memory defaults to 0
+    fn peek(&self, address: usize) -> isize {
+        *self.ram.get(&address).unwrap_or(&0)
+    }
+
+    /// Read value at an address, unset memory defaults to 0
+    fn read(&mut self, address: usize) -> isize {
+        if let Some(hook) = &mut self.hook {
+            hook.read(address);
+        }
+        self.peek(address)
     }
 
     /// Read value of current pointer and move to next
     fn advance_ptr(&mut self) -> isize {
-        let val = *self.ram.entry(self.main_pointer).or_insert(0);
+        let val = self.peek(self.main_pointer);
         self.main_pointer += 1;
         val
     }
 
+    /// Offset from the relative base pointer, must not be negative
+    fn relative_address(&self, offset: isize) -> Result<usize, String> {
+        (self.relative_pointer as isize)
+            .checked_add(offset)
+            .ok_or_else(|| "Relative address is out of range".to_string())
+            .and_then(instructions::address)
+    }
+
     /// Advances main pointer and retrieves parameter value
     fn read_parameter(&mut self, modes: &mut ParameterModeParser) -> Result<isize, String> {
         let val = self.advance_ptr();
         match modes.next().unwrap()? {
-            ParameterMode::Position => Ok(*self.ram.entry(val as usize).or_insert(0)),
+            ParameterMode::Position => Ok(self.read(instructions::address(val)?)),
             ParameterMode::Immediate => Ok(val),
-            ParameterMode::Relative => Ok(*self
-                .ram
-                .entry((val + self.relative_pointer as isize) as usize)
-                .or_insert(0)),
+            ParameterMode::Relative => {
+                let address = self.relative_address(val)?;
+                Ok(self.read(address))
+            }
         }
     }
 
-    /// Advances main pointer and sets the write pointer
-    fn set_write_pointer(&mut self, modes: &mut ParameterModeParser) -> Result<(), String> {
+    /// Advances main pointer and resolves the address of a write parameter
+    fn read_write_address(&mut self, modes: &mut ParameterModeParser) -> Result<usize, String> {
         let val = self.advance_ptr();
-        let w_ptr = match modes.next().unwrap()? {
-            ParameterMode::Position => val as usize,
-            ParameterMode::Relative => (val + self.relative_pointer as isize) as usize,
+        match modes.next().unwrap()? {
+            ParameterMode::Position => instructions::address(val),
+            ParameterMode::Relative => self.relative_address(val),
             ParameterMode::Immediate => {
-                return Err("Output pointers do not support Immediate Mode!".to_string())
+                Err("Output pointers do not support Immediate Mode!".to_string())
             }
-        };
+        }
+    }
 
-        self.w_ptr = Some(w_ptr);
+    /// Write value to the current write pointers location
+    fn write(&mut self, value: isize) -> Result<(), String> {
+        let address = self
+            .w_ptr
+            .take()
+            .ok_or("Input sent while the Computer is not waiting for input")?;
+        self.write_to(address, value);
         Ok(())
     }
 
-    /// Write value to the current write pointers location
-    fn write(&mut self, value: isize) {
-        self.ram.insert(self.w_ptr.unwrap(), value);
+    /// Write value to a memory address
+    fn write_to(&mut self, address: usize, value: isize) {
+        let previous = self.ram.insert(address, value);
+        if let Some(history) = &mut self.history {
+            history.record_write(address, previous);
+        }
+        if let Some(detector) = &mut self.loop_detector {
+            detector.record_write(address, previous.unwrap_or(0), value);
+        }
+        if let Some(hook) = &mut self.hook {
+            hook.write(address, value);
+        }
     }
 
     /// Read instructions to determine Opcode
     /// Reading instruction will advance the main pointer
     fn read_instruction(&mut self) -> Result<OpCode, Box<dyn error::Error>> {
-        let mode_code = self.advance_ptr() as usize;
+        let mode_code = self.advance_ptr();
+        if mode_code < 0 {
+            return Err(format!("Unknown Opcode Instruction: {}", mode_code).into());
+        }
+        let mode_code = mode_code as usize;
         let code = mode_code % 100;
         let mut modes = ParameterModeParser::new(mode_code / 100);
 
-        match code {
-            1 => {
-                let value_1 = self.read_parameter(&mut modes)?;
-                let value_2 = self.read_parameter(&mut modes)?;
-                self.set_write_pointer(&mut modes)?;
-                Ok(OpCode::Add { value_1, value_2 })
-            }
-            2 => {
-                let value_1 = self.read_parameter(&mut modes)?;
-                let value_2 = self.read_parameter(&mut modes)?;
-                self.set_write_pointer(&mut modes)?;
-
-                Ok(OpCode::Mul { value_1, value_2 })
-            }
-            3 => {
-                self.set_write_pointer(&mut modes)?;
-
-                Ok(OpCode::Input { input: None })
-            }
-            4 => {
-                let value_1 = self.read_parameter(&mut modes)?;
-                self.w_ptr = None;
-
-                Ok(OpCode::Output { value_1 })
-            }
-            5 => {
-                let value_1 = self.read_parameter(&mut modes)?;
-                let value_2 = self.read_parameter(&mut modes)?;
-                self.w_ptr = None;
-                Ok(OpCode::JumpIfTrue { value_1, value_2 })
-            }
-            6 => {
-                let value_1 = self.read_parameter(&mut modes)?;
-                let value_2 = self.read_parameter(&mut modes)?;
-                self.w_ptr = None;
-                Ok(OpCode::JumpIfFalse { value_1, value_2 })
-            }
-            7 => {
-                let value_1 = self.read_parameter(&mut modes)?;
-                let value_2 = self.read_parameter(&mut modes)?;
-                self.set_write_pointer(&mut modes)?;
-                Ok(OpCode::LessThan { value_1, value_2 })
-            }
-            8 => {
-                let value_1 = self.read_parameter(&mut modes)?;
-                let value_2 = self.read_parameter(&mut modes)?;
-                self.set_write_pointer(&mut modes)?;
-                Ok(OpCode::Equals { value_1, value_2 })
-            }
-            9 => {
-                let value_1 = self.read_parameter(&mut modes)?;
-                Ok(OpCode::UpdateRelativePointer { value_1 })
-            }
-            99 => {
-                self.w_ptr = None;
-                Ok(OpCode::Halt)
-            }
-            _ => Err(Box::new(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Unknown Opcode Instruction: {}", code),
-            ))),
+        let instruction_set = Arc::clone(&self.instruction_set);
+        let instruction = instruction_set
+            .get(code)
+            .ok_or_else(|| io::Error::other(format!("Unknown Opcode Instruction: {}", code)))?;
+
+        let mut args = Vec::with_capacity(instruction.parameters().len());
+        for parameter in instruction.parameters() {
+            args.push(match parameter {
+                Parameter::Read => self.read_parameter(&mut modes)?,
+                Parameter::Write => self.read_write_address(&mut modes)? as isize,
+            });
         }
+        self.w_ptr = None;
+
+        Ok(OpCode { code, args })
     }
 
     /// Execute a given instruction
-    fn execute_instruction(&mut self, instruction: OpCode) -> Option<HaltedState> {
-        match instruction {
-            OpCode::Add { value_1, value_2 } => {
-                self.write(value_1 + value_2);
-                None
-            }
-            OpCode::Mul { value_1, value_2 } => {
-                self.write(value_1 * value_2);
-                None
-            }
-            OpCode::Input { input: Some(input) } => {
-                self.write(input);
-                None
-            }
-            OpCode::Input { input: None } => Some(HaltedState::Input),
-            OpCode::Output { value_1 } => Some(HaltedState::Output(value_1)),
-            OpCode::JumpIfTrue { value_1, value_2 } => {
-                if value_1 != 0 {
-                    self.main_pointer = value_2 as usize;
-                }
-                None
-            }
-            OpCode::JumpIfFalse { value_1, value_2 } => {
-                if value_1 == 0 {
-                    self.main_pointer = value_2 as usize;
-                }
-                None
-            }
-            OpCode::LessThan { value_1, value_2 } => {
-                if value_1 < value_2 {
-                    self.write(1);
-                } else {
-                    self.write(0);
-                }
-                None
-            }
-            OpCode::Equals { value_1, value_2 } => {
-                if value_1 == value_2 {
-                    self.write(1);
-                } else {
-                    self.write(0);
-                }
-                None
-            }
-            OpCode::UpdateRelativePointer { value_1 } => {
-                self.relative_pointer = (self.relative_pointer as isize + value_1) as usize;
-                None
-            }
-            OpCode::Halt => Some(HaltedState::Halt),
-        }
+    fn execute_instruction(
+        &mut self,
+        instruction: OpCode,
+    ) -> Result<Option<HaltedState>, Box<dyn error::Error>> {
+        let instruction_set = Arc::clone(&self.instruction_set);
+        let execute = instruction_set
+            .get(instruction.code)
+            .ok_or_else(|| format!("Unknown Opcode Instruction: {}", instruction.code))?;
+
+        Ok(execute.execute(&mut Context::new(self), &instruction.args)?)
     }
 
     /// Execute an input to the current input
-    pub fn send_input(&mut self, input: isize) {
-        let input = Some(input);
-        self.execute_instruction(OpCode::Input { input });
-    }
-}
-
-/// Helper function to parse memory input from file
-pub fn parse_mem<T>(mut reader: T) -> Result<HashMap<usize, isize>, Box<dyn error::Error>>
-where
-    T: io::BufRead,
-{
-    let mut mem: HashMap<usize, isize> = HashMap::new();
-
-    let mut line = String::new();
-    reader.read_to_string(&mut line)?;
-
-    for (index, code) in line.split(",").enumerate() {
-        mem.insert(index, code.parse()?);
+    /// fails if the Computer has not halted waiting for input
+    pub fn send_input(&mut self, input: isize) -> Result<(), Box<dyn error::Error>> {
+        Ok(self.write(input)?)
     }
-
-    Ok(mem)
 }
 
 #[cfg(test)]
@@ -310,11 +359,15 @@ pub mod tests {
         let mut test_computer = Computer::new(&expected_ram);
         test_computer.ram = vec![1, 1, 1, 1].into_iter().enumerate().collect();
         test_computer.main_pointer = 99;
+        test_computer.relative_pointer = 7;
+        test_computer.w_ptr = Some(3);
 
         test_computer.reset();
 
         assert_eq!(test_computer.ram, expected_ram);
         assert_eq!(test_computer.main_pointer, 0);
+        assert_eq!(test_computer.relative_pointer, 0);
+        assert!(test_computer.send_input(1).is_err());
     }
 
     #[test]
@@ -335,18 +388,13 @@ pub mod tests {
         let mut test_computer = Computer::new(&test_ram);
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::Add {
-                value_1: 0,
-                value_2: 3,
-            }
-        ));
-        assert_eq!(test_computer.w_ptr.unwrap(), 0);
+        assert_eq!(opcode.code, 1);
+        assert_eq!(opcode.args[..2], [0, 3]);
+        assert_eq!(opcode.args[2], 0);
 
-        let result = test_computer.execute_instruction(opcode);
+        let result = test_computer.execute_instruction(opcode).unwrap();
 
-        assert!(matches!(result, None));
+        assert!(result.is_none());
         assert_eq!(test_computer.ram[&0], 3);
         assert_eq!(test_computer.main_pointer, 4);
     }
@@ -358,18 +406,13 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::Mul {
-                value_1: 4,
-                value_2: 8,
-            }
-        ));
-        assert_eq!(test_computer.w_ptr.unwrap(), 0);
+        assert_eq!(opcode.code, 2);
+        assert_eq!(opcode.args[..2], [4, 8]);
+        assert_eq!(opcode.args[2], 0);
 
-        let result = test_computer.execute_instruction(opcode);
+        let result = test_computer.execute_instruction(opcode).unwrap();
 
-        assert!(matches!(result, None));
+        assert!(result.is_none());
         assert_eq!(test_computer.ram[&0], 32);
         assert_eq!(test_computer.main_pointer, 4);
     }
@@ -381,13 +424,15 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(opcode, OpCode::Input { input: None }));
-        assert_eq!(test_computer.w_ptr.unwrap(), 2);
+        assert_eq!(opcode.code, 3);
+        assert_eq!(opcode.args, vec![2]);
 
-        let result = test_computer.execute_instruction(opcode);
+        let result = test_computer.execute_instruction(opcode).unwrap();
         assert!(matches!(result.unwrap(), HaltedState::Input));
-        test_computer.send_input(99);
+        assert_eq!(test_computer.w_ptr.unwrap(), 2);
+        test_computer.send_input(99).unwrap();
         assert_eq!(test_computer.ram[&2], 99);
+        assert!(test_computer.send_input(99).is_err());
     }
 
     #[test]
@@ -397,10 +442,11 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(opcode, OpCode::Output { value_1: 1000 }));
+        assert_eq!(opcode.code, 4);
+        assert_eq!(opcode.args, vec![1000]);
         assert_eq!(test_computer.w_ptr, None);
 
-        let result = test_computer.execute_instruction(opcode);
+        let result = test_computer.execute_instruction(opcode).unwrap();
         assert!(matches!(result.unwrap(), HaltedState::Output(1000)));
     }
 
@@ -411,18 +457,13 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::JumpIfTrue {
-                value_1: 1,
-                value_2: 0,
-            }
-        ));
+        assert_eq!(opcode.code, 5);
+        assert_eq!(opcode.args[..2], [1, 0]);
         assert_eq!(test_computer.main_pointer, 3);
         assert_eq!(test_computer.w_ptr, None);
 
-        let result = test_computer.execute_instruction(opcode);
-        assert!(matches!(result, None));
+        let result = test_computer.execute_instruction(opcode).unwrap();
+        assert!(result.is_none());
         assert_eq!(test_computer.main_pointer, 0);
 
         let test_ram = vec![5, 3, 0, 0].into_iter().enumerate().collect();
@@ -430,17 +471,12 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::JumpIfTrue {
-                value_1: 0,
-                value_2: 5,
-            }
-        ));
+        assert_eq!(opcode.code, 5);
+        assert_eq!(opcode.args[..2], [0, 5]);
         assert_eq!(test_computer.main_pointer, 3);
         assert_eq!(test_computer.w_ptr, None);
-        let result = test_computer.execute_instruction(opcode);
-        assert!(matches!(result, None));
+        let result = test_computer.execute_instruction(opcode).unwrap();
+        assert!(result.is_none());
         assert_eq!(test_computer.main_pointer, 3);
     }
 
@@ -451,18 +487,13 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::JumpIfFalse {
-                value_1: 1,
-                value_2: 0,
-            }
-        ));
+        assert_eq!(opcode.code, 6);
+        assert_eq!(opcode.args[..2], [1, 0]);
         assert_eq!(test_computer.main_pointer, 3);
         assert_eq!(test_computer.w_ptr, None);
 
-        let result = test_computer.execute_instruction(opcode);
-        assert!(matches!(result, None));
+        let result = test_computer.execute_instruction(opcode).unwrap();
+        assert!(result.is_none());
         assert_eq!(test_computer.main_pointer, 3);
 
         let test_ram = vec![6, 3, 0, 0].into_iter().enumerate().collect();
@@ -470,17 +501,12 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::JumpIfFalse {
-                value_1: 0,
-                value_2: 6,
-            }
-        ));
+        assert_eq!(opcode.code, 6);
+        assert_eq!(opcode.args[..2], [0, 6]);
         assert_eq!(test_computer.main_pointer, 3);
         assert_eq!(test_computer.w_ptr, None);
-        let result = test_computer.execute_instruction(opcode);
-        assert!(matches!(result, None));
+        let result = test_computer.execute_instruction(opcode).unwrap();
+        assert!(result.is_none());
         assert_eq!(test_computer.main_pointer, 6);
     }
 
@@ -491,18 +517,13 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::LessThan {
-                value_1: 1,
-                value_2: 2,
-            }
-        ));
-        assert_eq!(test_computer.w_ptr.unwrap(), 4);
+        assert_eq!(opcode.code, 7);
+        assert_eq!(opcode.args[..2], [1, 2]);
+        assert_eq!(opcode.args[2], 4);
 
-        let result = test_computer.execute_instruction(opcode);
+        let result = test_computer.execute_instruction(opcode).unwrap();
 
-        assert!(matches!(result, None));
+        assert!(result.is_none());
         assert_eq!(test_computer.ram[&4], 1);
         assert_eq!(test_computer.main_pointer, 4);
         let test_ram = vec![7, 0, 3, 4, 18].into_iter().enumerate().collect();
@@ -510,18 +531,13 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::LessThan {
-                value_1: 7,
-                value_2: 4,
-            }
-        ));
-        assert_eq!(test_computer.w_ptr.unwrap(), 4);
+        assert_eq!(opcode.code, 7);
+        assert_eq!(opcode.args[..2], [7, 4]);
+        assert_eq!(opcode.args[2], 4);
 
-        let result = test_computer.execute_instruction(opcode);
+        let result = test_computer.execute_instruction(opcode).unwrap();
 
-        assert!(matches!(result, None));
+        assert!(result.is_none());
         assert_eq!(test_computer.ram[&4], 0);
         assert_eq!(test_computer.main_pointer, 4);
     }
@@ -533,18 +549,13 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::Equals {
-                value_1: 1,
-                value_2: 1,
-            }
-        ));
-        assert_eq!(test_computer.w_ptr.unwrap(), 4);
+        assert_eq!(opcode.code, 8);
+        assert_eq!(opcode.args[..2], [1, 1]);
+        assert_eq!(opcode.args[2], 4);
 
-        let result = test_computer.execute_instruction(opcode);
+        let result = test_computer.execute_instruction(opcode).unwrap();
 
-        assert!(matches!(result, None));
+        assert!(result.is_none());
         assert_eq!(test_computer.ram[&4], 1);
         assert_eq!(test_computer.main_pointer, 4);
         let test_ram = vec![8, 0, 3, 4, 18].into_iter().enumerate().collect();
@@ -552,18 +563,13 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::Equals {
-                value_1: 8,
-                value_2: 4,
-            }
-        ));
-        assert_eq!(test_computer.w_ptr.unwrap(), 4);
+        assert_eq!(opcode.code, 8);
+        assert_eq!(opcode.args[..2], [8, 4]);
+        assert_eq!(opcode.args[2], 4);
 
-        let result = test_computer.execute_instruction(opcode);
+        let result = test_computer.execute_instruction(opcode).unwrap();
 
-        assert!(matches!(result, None));
+        assert!(result.is_none());
         assert_eq!(test_computer.ram[&4], 0);
         assert_eq!(test_computer.main_pointer, 4);
     }
@@ -575,15 +581,13 @@ pub mod tests {
 
         let opcode = test_computer.read_instruction().unwrap();
 
-        assert!(matches!(
-            opcode,
-            OpCode::UpdateRelativePointer { value_1: 44 }
-        ));
+        assert_eq!(opcode.code, 9);
+        assert_eq!(opcode.args, vec![44]);
         assert_eq!(test_computer.main_pointer, 2);
         assert_eq!(test_computer.w_ptr, None);
         assert_eq!(test_computer.relative_pointer, 0);
-        let result = test_computer.execute_instruction(opcode);
-        assert!(matches!(result, None));
+        let result = test_computer.execute_instruction(opcode).unwrap();
+        assert!(result.is_none());
         assert_eq!(test_computer.main_pointer, 2);
         assert_eq!(test_computer.w_ptr, None);
         assert_eq!(test_computer.relative_pointer, 44);
@@ -595,9 +599,10 @@ pub mod tests {
         let mut test_computer = Computer::new(&test_ram);
 
         let opcode = test_computer.read_instruction().unwrap();
-        assert!(matches!(opcode, OpCode::Halt));
+        assert_eq!(opcode.code, 99);
+        assert!(opcode.args.is_empty());
 
-        let result = test_computer.execute_instruction(opcode);
+        let result = test_computer.execute_instruction(opcode).unwrap();
 
         assert!(matches!(result, Some(HaltedState::Halt)));
         assert_eq!(test_computer.ram, test_ram);
@@ -0,0 +1,180 @@
+//! Property tests for the standard instruction set
+//!
+//! Each test builds a single instruction with random operands and parameter modes,
+//! decodes and executes it, then checks the result against plain Rust arithmetic.
+use std::collections::HashMap;
+
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use super::{Computer, HaltedState};
+
+/// Operands live from here on, the instruction itself always sits below
+const DATA: usize = 100;
+
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    Position,
+    Immediate,
+    Relative,
+}
+
+fn read_mode() -> impl Strategy<Value = Mode> {
+    prop_oneof![
+        Just(Mode::Position),
+        Just(Mode::Immediate),
+        Just(Mode::Relative)
+    ]
+}
+
+fn write_mode() -> impl Strategy<Value = Mode> {
+    prop_oneof![Just(Mode::Position), Just(Mode::Relative)]
+}
+
+/// Address a parameter reads from or writes to when it is not in Immediate Mode
+fn address(index: usize) -> usize {
+    DATA + 10 * index
+}
+
+/// Memory holding one instruction at `start`, each parameter has a mode and the value it should see
+/// write parameters target `address(index)`, their value is ignored
+fn setup(
+    code: isize,
+    start: usize,
+    base: usize,
+    params: &[(Mode, isize)],
+) -> HashMap<usize, isize> {
+    let mut mem = HashMap::new();
+    let mut instruction = code;
+    for (index, (mode, value)) in params.iter().enumerate() {
+        let target = address(index);
+        let (digit, raw) = match mode {
+            Mode::Position => (0, target as isize),
+            Mode::Immediate => (1, *value),
+            Mode::Relative => (2, target as isize - base as isize),
+        };
+        instruction += digit * 10isize.pow(index as u32 + 2);
+        mem.insert(start + 1 + index, raw);
+        mem.insert(target, *value);
+    }
+    mem.insert(start, instruction);
+    mem
+}
+
+/// Decode and execute the instruction at `start`
+/// decoding must advance the main pointer past the instruction
+fn execute(
+    mem: &HashMap<usize, isize>,
+    start: usize,
+    base: usize,
+) -> (Computer<'_>, Option<HaltedState>) {
+    let mut computer = Computer::new(mem);
+    computer.main_pointer = start;
+    computer.relative_pointer = base;
+
+    let opcode = computer.read_instruction().unwrap();
+    let width = computer.instruction_set().get(opcode.code).unwrap().width();
+    assert_eq!(computer.main_pointer, start + width);
+
+    let state = computer.execute_instruction(opcode).unwrap();
+    (computer, state)
+}
+
+proptest! {
+    #[test]
+    fn test_arithmetic(
+        code in select(vec![1, 2]),
+        start in 0..DATA - 4,
+        base in 0..1000usize,
+        a in (read_mode(), -1_000_000_000isize..1_000_000_000),
+        b in (read_mode(), -1_000_000_000isize..1_000_000_000),
+        c in write_mode(),
+    ) {
+        let mem = setup(code, start, base, &[a, b, (c, 0)]);
+        let (computer, state) = execute(&mem, start, base);
+
+        let expected = if code == 1 { a.1 + b.1 } else { a.1 * b.1 };
+        prop_assert!(state.is_none());
+        prop_assert_eq!(computer.ram[&address(2)], expected);
+        prop_assert_eq!(computer.main_pointer, start + 4);
+    }
+
+    #[test]
+    fn test_comparisons(
+        code in select(vec![7, 8]),
+        start in 0..DATA - 4,
+        base in 0..1000usize,
+        a in (read_mode(), -3isize..3),
+        b in (read_mode(), -3isize..3),
+        c in write_mode(),
+    ) {
+        let mem = setup(code, start, base, &[a, b, (c, 0)]);
+        let (computer, state) = execute(&mem, start, base);
+
+        let expected = if code == 7 { a.1 < b.1 } else { a.1 == b.1 };
+        prop_assert!(state.is_none());
+        prop_assert_eq!(computer.ram[&address(2)], expected as isize);
+    }
+
+    #[test]
+    fn test_jumps(
+        code in select(vec![5, 6]),
+        start in 0..DATA - 3,
+        base in 0..1000usize,
+        condition in (read_mode(), -2isize..2),
+        target in (read_mode(), 0isize..1000),
+    ) {
+        let mem = setup(code, start, base, &[condition, target]);
+        let (computer, state) = execute(&mem, start, base);
+
+        let taken = (condition.1 != 0) == (code == 5);
+        let expected = if taken { target.1 as usize } else { start + 3 };
+        prop_assert!(state.is_none());
+        prop_assert_eq!(computer.main_pointer, expected);
+    }
+
+    #[test]
+    fn test_relative_base(
+        start in 0..DATA - 2,
+        base in 0..1000usize,
+        mode in read_mode(),
+        updated in 0..2000usize,
+    ) {
+        let offset = updated as isize - base as isize;
+        let mem = setup(9, start, base, &[(mode, offset)]);
+        let (computer, state) = execute(&mem, start, base);
+
+        prop_assert!(state.is_none());
+        prop_assert_eq!(computer.relative_pointer, updated);
+    }
+
+    #[test]
+    fn test_io(
+        start in 0..DATA - 2,
+        base in 0..1000usize,
+        read in (read_mode(), any::<isize>()),
+        write in write_mode(),
+        input in any::<isize>(),
+    ) {
+        let mem = setup(4, start, base, &[read]);
+        let (_, state) = execute(&mem, start, base);
+        prop_assert_eq!(state, Some(HaltedState::Output(read.1)));
+
+        let mem = setup(3, start, base, &[(write, 0)]);
+        let (mut computer, state) = execute(&mem, start, base);
+        prop_assert_eq!(state, Some(HaltedState::Input));
+        computer.send_input(input).unwrap();
+        prop_assert_eq!(computer.ram[&address(0)], input);
+        prop_assert_eq!(computer.main_pointer, start + 2);
+    }
+
+    #[test]
+    fn test_halt(start in 0..DATA - 1, modes in 0isize..1000) {
+        // halt has no parameters, so any mode digits are ignored
+        let mem = setup(99 + modes * 100, start, 0, &[]);
+        let (computer, state) = execute(&mem, start, 0);
+
+        prop_assert_eq!(state, Some(HaltedState::Halt));
+        prop_assert_eq!(&computer.ram, &mem);
+    }
+}
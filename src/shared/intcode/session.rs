@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+use std::{error, fmt, io};
+
+use super::{Computer, HaltedState};
+
+/// Hash a program (FNV-1a over address ordered cells) to identify it in a session
+pub fn program_hash(mem: &HashMap<usize, isize>) -> u64 {
+    let mut cells: Vec<(&usize, &isize)> = mem.iter().collect();
+    cells.sort();
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for (address, value) in cells {
+        for byte in (*address as u64)
+            .to_le_bytes()
+            .iter()
+            .chain((*value as i64).to_le_bytes().iter())
+        {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// A single I/O event, tagged with the instruction count and address it occurred at
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    Input {
+        count: usize,
+        address: usize,
+        value: isize,
+    },
+    Output {
+        count: usize,
+        address: usize,
+        value: isize,
+    },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Input {
+                count,
+                address,
+                value,
+            } => write!(f, "input {} {} {}", count, address, value),
+            Event::Output {
+                count,
+                address,
+                value,
+            } => write!(f, "output {} {} {}", count, address, value),
+        }
+    }
+}
+
+/// Recorded I/O session of a program
+/// the hash is of the program as loaded, memory patches are kept separately and applied on replay
+#[derive(Debug, PartialEq)]
+pub struct Session {
+    pub hash: u64,
+    pub pokes: Vec<(usize, isize)>,
+    pub events: Vec<Event>,
+}
+
+impl Session {
+    /// Write session as text, one event per line
+    pub fn write<W>(&self, mut writer: W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        writeln!(writer, "hash {:016x}", self.hash)?;
+        for (address, value) in &self.pokes {
+            writeln!(writer, "poke {} {}", address, value)?;
+        }
+        for event in &self.events {
+            writeln!(writer, "{}", event)?;
+        }
+        writer.flush()
+    }
+
+    /// Parse a session written by `Session::write`
+    pub fn parse<T>(reader: T) -> Result<Self, Box<dyn error::Error>>
+    where
+        T: io::BufRead,
+    {
+        let mut hash = None;
+        let mut pokes = Vec::new();
+        let mut events = Vec::new();
+
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let invalid = || format!("Invalid session line {}: {}", number + 1, line);
+
+            match fields.as_slice() {
+                [] => (),
+                ["hash", value] => {
+                    hash = Some(u64::from_str_radix(value, 16).map_err(|_| invalid())?)
+                }
+                ["poke", address, value] => pokes.push((
+                    address.parse().map_err(|_| invalid())?,
+                    value.parse().map_err(|_| invalid())?,
+                )),
+                [kind, count, address, value] => {
+                    let count = count.parse().map_err(|_| invalid())?;
+                    let address = address.parse().map_err(|_| invalid())?;
+                    let value = value.parse().map_err(|_| invalid())?;
+                    events.push(match *kind {
+                        "input" => Event::Input {
+                            count,
+                            address,
+                            value,
+                        },
+                        "output" => Event::Output {
+                            count,
+                            address,
+                            value,
+                        },
+                        _ => return Err(invalid().into()),
+                    });
+                }
+                _ => return Err(invalid().into()),
+            }
+        }
+
+        Ok(Self {
+            hash: hash.ok_or("Session is missing a program hash")?,
+            pokes,
+            events,
+        })
+    }
+}
+
+/// Records every input delivered to and output produced by a Computer
+pub struct Recorder<'c, 'a> {
+    computer: &'c mut Computer<'a>,
+    session: Session,
+}
+
+impl<'c, 'a> Recorder<'c, 'a> {
+    /// Start recording a Computer's session
+    pub fn new(computer: &'c mut Computer<'a>) -> Self {
+        let hash = program_hash(computer.rom);
+        Self {
+            computer,
+            session: Session {
+                hash,
+                pokes: Vec::new(),
+                events: Vec::new(),
+            },
+        }
+    }
+
+    /// Patch memory before the Computer runs, recording the patch
+    pub fn poke(&mut self, address: usize, value: isize) {
+        self.computer.poke(address, value);
+        self.session.pokes.push((address, value));
+    }
+
+    /// Run the Computer, recording any output
+    pub fn run(&mut self) -> Result<HaltedState, Box<dyn error::Error>> {
+        let state = self.computer.run()?;
+        if let HaltedState::Output(value) = state {
+            self.session.events.push(Event::Output {
+                count: self.computer.instructions,
+                address: self.computer.instruction_pointer,
+                value,
+            });
+        }
+        Ok(state)
+    }
+
+    /// Send and record an input
+    pub fn send_input(&mut self, value: isize) -> Result<(), Box<dyn error::Error>> {
+        self.computer.send_input(value)?;
+        self.session.events.push(Event::Input {
+            count: self.computer.instructions,
+            address: self.computer.instruction_pointer,
+            value,
+        });
+        Ok(())
+    }
+
+    /// Recorded session so far
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+}
+
+/// First point at which a replay differs from its recorded session
+#[derive(Debug)]
+pub struct Divergence {
+    pub count: usize,
+    pub address: usize,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Replay diverged at instruction {} (address {}): expected {} but got {}",
+            self.count, self.address, self.expected, self.actual
+        )
+    }
+}
+
+impl error::Error for Divergence {}
+
+/// Replay a session on a freshly loaded Computer, applying the recorded memory patches then
+/// feeding recorded inputs and verifying outputs match, returns the number of events replayed
+pub fn replay(computer: &mut Computer, session: &Session) -> Result<usize, Box<dyn error::Error>> {
+    let hash = program_hash(computer.rom);
+    if hash != session.hash {
+        return Err(format!(
+            "Program hash {:016x} does not match session hash {:016x}",
+            hash, session.hash
+        )
+        .into());
+    }
+    for (address, value) in &session.pokes {
+        computer.poke(*address, *value);
+    }
+
+    let mut events = session.events.iter();
+    loop {
+        let state = computer.run()?;
+        let count = computer.instructions;
+        let address = computer.instruction_pointer;
+        let expected = events.next();
+
+        let actual = match (state, expected) {
+            (HaltedState::Halt, None) => return Ok(session.events.len()),
+            (HaltedState::Input, Some(event @ Event::Input { value, .. })) => {
+                let actual = Event::Input {
+                    count,
+                    address,
+                    value: *value,
+                };
+                if &actual == event {
+                    computer.send_input(*value)?;
+                    continue;
+                }
+                actual.to_string()
+            }
+            (HaltedState::Output(value), Some(event @ Event::Output { .. })) => {
+                let actual = Event::Output {
+                    count,
+                    address,
+                    value,
+                };
+                if &actual == event {
+                    continue;
+                }
+                actual.to_string()
+            }
+            (HaltedState::Input, _) => format!("input request {} {}", count, address),
+            (HaltedState::Output(value), _) => format!("output {} {} {}", count, address, value),
+            (HaltedState::Halt, _) => format!("halt {} {}", count, address),
+        };
+
+        return Err(Box::new(Divergence {
+            count,
+            address,
+            expected: match expected {
+                Some(event) => event.to_string(),
+                None => "halt".to_string(),
+            },
+            actual,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::parse_mem;
+    use super::*;
+
+    /// reads two inputs, outputs their sum and product
+    const PROGRAM: &str = "3,20,3,21,1,20,21,22,4,22,2,20,21,22,4,22,99";
+
+    fn record(mem: &HashMap<usize, isize>, pokes: &[(usize, isize)], inputs: &[isize]) -> Session {
+        let mut computer = Computer::new(mem);
+        let mut recorder = Recorder::new(&mut computer);
+        for (address, value) in pokes {
+            recorder.poke(*address, *value);
+        }
+        let mut inputs = inputs.iter();
+        loop {
+            match recorder.run().unwrap() {
+                HaltedState::Halt => break,
+                HaltedState::Input => recorder.send_input(*inputs.next().unwrap()).unwrap(),
+                HaltedState::Output(_) => (),
+            }
+        }
+        Session {
+            hash: recorder.session().hash,
+            pokes: recorder.session().pokes.clone(),
+            events: recorder.session().events.clone(),
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let mem = parse_mem(PROGRAM.as_bytes()).unwrap();
+        let session = record(&mem, &[], &[3, 4]);
+
+        assert_eq!(session.hash, program_hash(&mem));
+        assert_eq!(
+            session.events,
+            vec![
+                Event::Input {
+                    count: 1,
+                    address: 0,
+                    value: 3
+                },
+                Event::Input {
+                    count: 2,
+                    address: 2,
+                    value: 4
+                },
+                Event::Output {
+                    count: 4,
+                    address: 8,
+                    value: 7
+                },
+                Event::Output {
+                    count: 6,
+                    address: 14,
+                    value: 12
+                },
+            ]
+        );
+
+        let mut text = Vec::new();
+        session.write(&mut text).unwrap();
+        assert_eq!(Session::parse(text.as_slice()).unwrap(), session);
+    }
+
+    #[test]
+    fn test_replay() {
+        let mem = parse_mem(PROGRAM.as_bytes()).unwrap();
+        let mut session = record(&mem, &[], &[3, 4]);
+
+        let mut computer = Computer::new(&mem);
+        assert_eq!(replay(&mut computer, &session).unwrap(), 4);
+
+        session.events[3] = Event::Output {
+            count: 6,
+            address: 14,
+            value: 13,
+        };
+        let mut computer = Computer::new(&mem);
+        let error = replay(&mut computer, &session).unwrap_err();
+        let divergence = error.downcast_ref::<Divergence>().unwrap();
+        assert_eq!(divergence.count, 6);
+        assert_eq!(divergence.address, 14);
+
+        session.events.pop();
+        let mut computer = Computer::new(&mem);
+        let error = replay(&mut computer, &session).unwrap_err();
+        let divergence = error.downcast_ref::<Divergence>().unwrap();
+        assert_eq!(divergence.expected, "halt");
+    }
+
+    #[test]
+    fn test_replay_pokes() {
+        // patch the multiply into a second add
+        let mem = parse_mem(PROGRAM.as_bytes()).unwrap();
+        let mut session = record(&mem, &[(10, 1)], &[3, 4]);
+        assert_eq!(session.hash, program_hash(&mem));
+        assert_eq!(
+            session.events[3],
+            Event::Output {
+                count: 6,
+                address: 14,
+                value: 7
+            }
+        );
+
+        let mut text = Vec::new();
+        session.write(&mut text).unwrap();
+        let parsed = Session::parse(text.as_slice()).unwrap();
+        assert_eq!(parsed, session);
+
+        let mut computer = Computer::new(&mem);
+        assert_eq!(replay(&mut computer, &parsed).unwrap(), 4);
+
+        session.pokes.clear();
+        let mut computer = Computer::new(&mem);
+        assert!(replay(&mut computer, &session).is_err());
+    }
+}
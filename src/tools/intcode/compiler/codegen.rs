@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+
+use super::parser::{BinaryOp, Expr, Function, Statement, UnaryOp};
+use super::CompileError;
+
+const ADD: isize = 1;
+const MULTIPLY: isize = 2;
+const INPUT: isize = 3;
+const OUTPUT: isize = 4;
+const JUMP_IF_TRUE: isize = 5;
+const JUMP_IF_FALSE: isize = 6;
+const LESS_THAN: isize = 7;
+const EQUALS: isize = 8;
+const ADJUST_RELATIVE: isize = 9;
+const HALT: isize = 99;
+
+/// Frame slot holding the return address, the return value is left in the slot after it
+const RETURN_ADDRESS: isize = 0;
+const RETURN_VALUE: isize = 1;
+
+/// Instruction parameter
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operand {
+    Immediate(isize),
+    /// slot of the current stack frame
+    Relative(isize),
+    /// address of a label, resolved once all code is generated
+    Label(usize),
+}
+
+impl Operand {
+    fn mode(&self) -> isize {
+        match self {
+            Operand::Immediate(_) | Operand::Label(_) => 1,
+            Operand::Relative(_) => 2,
+        }
+    }
+}
+
+/// Slots of the function being generated
+/// a frame is laid out as the return address, parameters, locals and then temporaries
+struct Frame {
+    scopes: Vec<HashMap<String, isize>>,
+    next_local: isize,
+    temp_base: isize,
+    next_temp: isize,
+}
+
+impl Frame {
+    fn lookup(&self, name: &str, line: usize) -> Result<isize, CompileError> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+            .ok_or_else(|| CompileError {
+                line,
+                message: format!("Unknown variable {}", name),
+            })
+    }
+
+    fn declare(&mut self, name: &str, line: usize) -> Result<isize, CompileError> {
+        let slot = self.next_local;
+        let scope = self.scopes.last_mut().unwrap();
+        if scope.insert(name.to_string(), slot).is_some() {
+            return Err(CompileError {
+                line,
+                message: format!("Variable {} is already declared", name),
+            });
+        }
+        self.next_local += 1;
+        Ok(slot)
+    }
+
+    fn temp(&mut self) -> isize {
+        self.next_temp += 1;
+        self.next_temp - 1
+    }
+}
+
+/// Number of variables declared anywhere within statements
+fn count_locals(statements: &[Statement]) -> usize {
+    statements
+        .iter()
+        .map(|statement| match statement {
+            Statement::Var { .. } => 1,
+            Statement::If {
+                then, otherwise, ..
+            } => count_locals(then) + count_locals(otherwise),
+            Statement::While { body, .. } => count_locals(body),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Generates Intcode for a parsed program
+struct Generator {
+    code: Vec<isize>,
+    labels: Vec<Option<usize>>,
+    fixups: Vec<(usize, usize)>,
+    /// function name to its label and number of parameters
+    functions: HashMap<String, (usize, usize)>,
+}
+
+impl Generator {
+    fn label(&mut self) -> usize {
+        self.labels.push(None);
+        self.labels.len() - 1
+    }
+
+    fn place(&mut self, label: usize) {
+        self.labels[label] = Some(self.code.len());
+    }
+
+    fn emit(&mut self, opcode: isize, operands: &[Operand]) {
+        let modes: isize = operands
+            .iter()
+            .enumerate()
+            .map(|(index, operand)| operand.mode() * 10_isize.pow(index as u32 + 2))
+            .sum();
+        self.code.push(opcode + modes);
+
+        for operand in operands {
+            match operand {
+                Operand::Immediate(value) | Operand::Relative(value) => self.code.push(*value),
+                Operand::Label(label) => {
+                    self.fixups.push((self.code.len(), *label));
+                    self.code.push(0);
+                }
+            }
+        }
+    }
+
+    fn copy(&mut self, from: Operand, slot: isize) {
+        if from != Operand::Relative(slot) {
+            self.emit(ADD, &[from, Operand::Immediate(0), Operand::Relative(slot)]);
+        }
+    }
+
+    fn function(&mut self, function: &Function) -> Result<(), CompileError> {
+        let (label, _) = self.functions[&function.name];
+        self.place(label);
+
+        let parameters = function.parameters.len() as isize;
+        let temp_base = 1 + parameters + count_locals(&function.body) as isize;
+        let mut frame = Frame {
+            scopes: vec![HashMap::new()],
+            next_local: 1,
+            temp_base,
+            next_temp: temp_base,
+        };
+        for parameter in &function.parameters {
+            frame.declare(parameter, function.line)?;
+        }
+
+        self.block(&mut frame, &function.body)?;
+        self.ret(Operand::Immediate(0));
+        Ok(())
+    }
+
+    fn ret(&mut self, value: Operand) {
+        self.copy(value, RETURN_VALUE);
+        self.emit(
+            JUMP_IF_TRUE,
+            &[Operand::Immediate(1), Operand::Relative(RETURN_ADDRESS)],
+        );
+    }
+
+    fn block(&mut self, frame: &mut Frame, statements: &[Statement]) -> Result<(), CompileError> {
+        frame.scopes.push(HashMap::new());
+        for statement in statements {
+            self.statement(frame, statement)?;
+            frame.next_temp = frame.temp_base;
+        }
+        frame.scopes.pop();
+        Ok(())
+    }
+
+    fn statement(&mut self, frame: &mut Frame, statement: &Statement) -> Result<(), CompileError> {
+        match statement {
+            Statement::Var { name, value, line } => {
+                let value = self.expression(frame, value)?;
+                let slot = frame.declare(name, *line)?;
+                self.copy(value, slot);
+            }
+            Statement::Assign { name, value, line } => {
+                let slot = frame.lookup(name, *line)?;
+                let value = self.expression(frame, value)?;
+                self.copy(value, slot);
+            }
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let condition = self.expression(frame, condition)?;
+                frame.next_temp = frame.temp_base;
+                let otherwise_label = self.label();
+                self.emit(JUMP_IF_FALSE, &[condition, Operand::Label(otherwise_label)]);
+                self.block(frame, then)?;
+
+                if otherwise.is_empty() {
+                    self.place(otherwise_label);
+                } else {
+                    let end = self.label();
+                    self.emit(JUMP_IF_TRUE, &[Operand::Immediate(1), Operand::Label(end)]);
+                    self.place(otherwise_label);
+                    self.block(frame, otherwise)?;
+                    self.place(end);
+                }
+            }
+            Statement::While { condition, body } => {
+                let start = self.label();
+                let end = self.label();
+                self.place(start);
+                let condition = self.expression(frame, condition)?;
+                frame.next_temp = frame.temp_base;
+                self.emit(JUMP_IF_FALSE, &[condition, Operand::Label(end)]);
+                self.block(frame, body)?;
+                self.emit(
+                    JUMP_IF_TRUE,
+                    &[Operand::Immediate(1), Operand::Label(start)],
+                );
+                self.place(end);
+            }
+            Statement::Return(value) => {
+                let value = match value {
+                    Some(value) => self.expression(frame, value)?,
+                    None => Operand::Immediate(0),
+                };
+                self.ret(value);
+            }
+            Statement::Expression(expr) => {
+                self.expression(frame, expr)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn expression(&mut self, frame: &mut Frame, expr: &Expr) -> Result<Operand, CompileError> {
+        Ok(match expr {
+            Expr::Number(value) => Operand::Immediate(*value),
+            Expr::Variable { name, line } => Operand::Relative(frame.lookup(name, *line)?),
+            Expr::Call { name, args, line } => self.call(frame, name, args, *line)?,
+            Expr::Unary(op, value) => {
+                let value = self.expression(frame, value)?;
+                let result = frame.temp();
+                match op {
+                    UnaryOp::Negate => self.emit(
+                        MULTIPLY,
+                        &[value, Operand::Immediate(-1), Operand::Relative(result)],
+                    ),
+                    UnaryOp::Not => self.emit(
+                        EQUALS,
+                        &[value, Operand::Immediate(0), Operand::Relative(result)],
+                    ),
+                }
+                Operand::Relative(result)
+            }
+            Expr::Binary(op, left, right) => {
+                let left = self.expression(frame, left)?;
+                let right = self.expression(frame, right)?;
+                let result = frame.temp();
+                self.binary(frame, *op, left, right, Operand::Relative(result));
+                Operand::Relative(result)
+            }
+        })
+    }
+
+    fn binary(
+        &mut self,
+        frame: &mut Frame,
+        op: BinaryOp,
+        left: Operand,
+        right: Operand,
+        result: Operand,
+    ) {
+        let zero = Operand::Immediate(0);
+        match op {
+            BinaryOp::Add => self.emit(ADD, &[left, right, result]),
+            BinaryOp::Multiply => self.emit(MULTIPLY, &[left, right, result]),
+            BinaryOp::Subtract => {
+                let negated = Operand::Relative(frame.temp());
+                self.emit(MULTIPLY, &[right, Operand::Immediate(-1), negated]);
+                self.emit(ADD, &[left, negated, result]);
+            }
+            BinaryOp::Less => self.emit(LESS_THAN, &[left, right, result]),
+            BinaryOp::Greater => self.emit(LESS_THAN, &[right, left, result]),
+            BinaryOp::LessEqual => {
+                self.emit(LESS_THAN, &[right, left, result]);
+                self.emit(EQUALS, &[result, zero, result]);
+            }
+            BinaryOp::GreaterEqual => {
+                self.emit(LESS_THAN, &[left, right, result]);
+                self.emit(EQUALS, &[result, zero, result]);
+            }
+            BinaryOp::Equal => self.emit(EQUALS, &[left, right, result]),
+            BinaryOp::NotEqual => {
+                self.emit(EQUALS, &[left, right, result]);
+                self.emit(EQUALS, &[result, zero, result]);
+            }
+            // both sides are always evaluated
+            BinaryOp::And | BinaryOp::Or => {
+                let other = Operand::Relative(frame.temp());
+                self.emit(EQUALS, &[left, zero, result]);
+                self.emit(EQUALS, &[right, zero, other]);
+                if op == BinaryOp::And {
+                    self.emit(ADD, &[result, other, result]);
+                } else {
+                    self.emit(MULTIPLY, &[result, other, result]);
+                }
+                self.emit(EQUALS, &[result, zero, result]);
+            }
+        }
+    }
+
+    fn call(
+        &mut self,
+        frame: &mut Frame,
+        name: &str,
+        args: &[Expr],
+        line: usize,
+    ) -> Result<Operand, CompileError> {
+        let arity = |expected: usize| {
+            if args.len() == expected {
+                return Ok(());
+            }
+            Err(CompileError {
+                line,
+                message: format!(
+                    "{} expects {} arguments but was given {}",
+                    name,
+                    expected,
+                    args.len()
+                ),
+            })
+        };
+
+        match name {
+            "input" => {
+                arity(0)?;
+                let result = frame.temp();
+                self.emit(INPUT, &[Operand::Relative(result)]);
+                return Ok(Operand::Relative(result));
+            }
+            "output" => {
+                arity(1)?;
+                let value = self.expression(frame, &args[0])?;
+                self.emit(OUTPUT, &[value]);
+                return Ok(Operand::Immediate(0));
+            }
+            _ => (),
+        }
+
+        let (label, parameters) = *self.functions.get(name).ok_or_else(|| CompileError {
+            line,
+            message: format!("Unknown function {}", name),
+        })?;
+        arity(parameters)?;
+
+        // the callee's frame starts just past the caller's live slots
+        let base = frame.next_temp;
+        frame.next_temp = base + 1 + args.len() as isize;
+        for (index, arg) in args.iter().enumerate() {
+            let value = self.expression(frame, arg)?;
+            self.copy(value, base + 1 + index as isize);
+        }
+
+        let return_label = self.label();
+        self.emit(
+            ADD,
+            &[
+                Operand::Label(return_label),
+                Operand::Immediate(0),
+                Operand::Relative(base + RETURN_ADDRESS),
+            ],
+        );
+        self.emit(ADJUST_RELATIVE, &[Operand::Immediate(base)]);
+        self.emit(
+            JUMP_IF_TRUE,
+            &[Operand::Immediate(1), Operand::Label(label)],
+        );
+        self.place(return_label);
+        self.emit(ADJUST_RELATIVE, &[Operand::Immediate(-base)]);
+
+        self.copy(Operand::Relative(base + RETURN_VALUE), base);
+        frame.next_temp = base + 1;
+        Ok(Operand::Relative(base))
+    }
+}
+
+/// Generate a memory image for a program, execution starts by calling `main`
+/// the stack is placed directly after the program
+pub fn generate(functions: &[Function]) -> Result<Vec<isize>, CompileError> {
+    let mut generator = Generator {
+        code: Vec::new(),
+        labels: Vec::new(),
+        fixups: Vec::new(),
+        functions: HashMap::new(),
+    };
+
+    for function in functions {
+        if ["input", "output"].contains(&function.name.as_str())
+            || generator.functions.contains_key(&function.name)
+        {
+            return Err(CompileError {
+                line: function.line,
+                message: format!("Function {} is already defined", function.name),
+            });
+        }
+        let label = generator.label();
+        generator
+            .functions
+            .insert(function.name.clone(), (label, function.parameters.len()));
+    }
+
+    let main = match generator.functions.get("main") {
+        Some((label, 0)) => *label,
+        Some(_) => {
+            return Err(CompileError {
+                line: 1,
+                message: "main must not take any parameters".to_string(),
+            })
+        }
+        None => {
+            return Err(CompileError {
+                line: 1,
+                message: "Program has no main function".to_string(),
+            })
+        }
+    };
+
+    let stack = generator.label();
+    let halt = generator.label();
+    generator.emit(ADJUST_RELATIVE, &[Operand::Label(stack)]);
+    generator.emit(
+        ADD,
+        &[
+            Operand::Label(halt),
+            Operand::Immediate(0),
+            Operand::Relative(RETURN_ADDRESS),
+        ],
+    );
+    generator.emit(JUMP_IF_TRUE, &[Operand::Immediate(1), Operand::Label(main)]);
+    generator.place(halt);
+    generator.emit(HALT, &[]);
+
+    for function in functions {
+        generator.function(function)?;
+    }
+    generator.place(stack);
+
+    let mut code = generator.code;
+    for (position, label) in generator.fixups {
+        code[position] = generator.labels[label].unwrap() as isize;
+    }
+    Ok(code)
+}
@@ -0,0 +1,137 @@
+use super::CompileError;
+
+/// Tokens of the language
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Number(isize),
+    Name(String),
+    Fn,
+    Var,
+    If,
+    Else,
+    While,
+    Return,
+    OpenParen,
+    CloseParen,
+    OpenBrace,
+    CloseBrace,
+    Comma,
+    Semicolon,
+    Assign,
+    Plus,
+    Minus,
+    Star,
+    Not,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+    End,
+}
+
+/// Token along with the line it was found on
+#[derive(Debug, Clone)]
+pub struct Lexeme {
+    pub token: Token,
+    pub line: usize,
+}
+
+/// Split source into tokens, `//` starts a comment running to the end of the line
+pub fn tokenize(source: &str) -> Result<Vec<Lexeme>, CompileError> {
+    let mut lexemes = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+                continue;
+            }
+            '0'..='9' => {
+                let mut digits = c.to_string();
+                while let Some(digit) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                    digits.push(*digit);
+                    chars.next();
+                }
+                Token::Number(digits.parse().map_err(|e| CompileError {
+                    line,
+                    message: format!("Invalid number {}: {}", digits, e),
+                })?)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut name = c.to_string();
+                while let Some(c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_') {
+                    name.push(*c);
+                    chars.next();
+                }
+                match name.as_str() {
+                    "fn" => Token::Fn,
+                    "var" => Token::Var,
+                    "if" => Token::If,
+                    "else" => Token::Else,
+                    "while" => Token::While,
+                    "return" => Token::Return,
+                    _ => Token::Name(name),
+                }
+            }
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '{' => Token::OpenBrace,
+            '}' => Token::CloseBrace,
+            ',' => Token::Comma,
+            ';' => Token::Semicolon,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '=' | '!' | '<' | '>' => {
+                let equals = chars.peek() == Some(&'=');
+                if equals {
+                    chars.next();
+                }
+                match (c, equals) {
+                    ('=', false) => Token::Assign,
+                    ('=', true) => Token::Equal,
+                    ('!', false) => Token::Not,
+                    ('!', true) => Token::NotEqual,
+                    ('<', false) => Token::Less,
+                    ('<', true) => Token::LessEqual,
+                    ('>', false) => Token::Greater,
+                    _ => Token::GreaterEqual,
+                }
+            }
+            '&' | '|' if chars.peek() == Some(&c) => {
+                chars.next();
+                if c == '&' {
+                    Token::And
+                } else {
+                    Token::Or
+                }
+            }
+            c => {
+                return Err(CompileError {
+                    line,
+                    message: format!("Unexpected character '{}'", c),
+                })
+            }
+        };
+        lexemes.push(Lexeme { token, line });
+    }
+
+    lexemes.push(Lexeme {
+        token: Token::End,
+        line,
+    });
+    Ok(lexemes)
+}
@@ -0,0 +1,197 @@
+//! Compiler for a tiny structured language targeting Intcode
+//!
+//! A program is a list of functions, execution starts at `main`.
+//! Values are integers, variables are declared with `var` and scoped to their block.
+//!
+//! ```text
+//! fn square(x) { return x * x; }
+//!
+//! fn main() {
+//!     var n = input();
+//!     var i = 1;
+//!     while (i <= n) {
+//!         output(square(i));
+//!         i = i + 1;
+//!     }
+//! }
+//! ```
+//!
+//! Expressions support `+ - *`, comparisons, `== !=`, `&& || !` and calls,
+//! `input()` reads a value and `output(x)` writes one.
+//! Each call gets a stack frame addressed through the relative base.
+use std::collections::HashMap;
+use std::{error, fmt};
+
+mod codegen;
+mod lexer;
+mod parser;
+
+/// Error raised when a program is unable to be compiled
+#[derive(Debug, PartialEq)]
+pub struct CompileError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for CompileError {}
+
+/// Compile source into a memory image
+pub fn compile(source: &str) -> Result<HashMap<usize, isize>, CompileError> {
+    let functions = parser::parse(lexer::tokenize(source)?)?;
+    Ok(codegen::generate(&functions)?
+        .into_iter()
+        .enumerate()
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shared::intcode::{Computer, HaltedState};
+
+    fn run(source: &str, inputs: &[isize]) -> Vec<isize> {
+        let mem = compile(source).unwrap();
+        let mut computer = Computer::new(&mem);
+        let mut inputs = inputs.iter();
+        let mut outputs = Vec::new();
+        loop {
+            match computer.run().unwrap() {
+                HaltedState::Input => computer.send_input(*inputs.next().unwrap()).unwrap(),
+                HaltedState::Output(value) => outputs.push(value),
+                HaltedState::Halt => return outputs,
+            }
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let source = "
+            fn main() {
+                var a = input();
+                var b = input();
+                output(a + b);
+                output(a - b);
+                output(a * b);
+                output(-(a + 1) * 2 + b);
+            }";
+        assert_eq!(run(source, &[7, 3]), vec![10, 4, 21, -13]);
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let source = "
+            fn main() {
+                var a = input();
+                var b = input();
+                output(a < b);
+                output(a <= b);
+                output(a > b);
+                output(a >= b);
+                output(a == b);
+                output(a != b);
+                output(a > 0 && b > 0);
+                output(a > 0 || b > 0);
+                output(!a);
+            }";
+        assert_eq!(run(source, &[2, 2]), vec![0, 1, 0, 1, 1, 0, 1, 1, 0]);
+        assert_eq!(run(source, &[-1, 5]), vec![1, 1, 0, 0, 0, 1, 0, 1, 0]);
+        assert_eq!(run(source, &[0, -5]), vec![0, 0, 1, 1, 0, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_control_flow() {
+        // fizzbuzz style classification, 3 for fizzbuzz, 1 for fizz, 2 for buzz, 0 otherwise
+        let source = "
+            fn divides(d, n) {
+                while (n > 0) { n = n - d; }
+                return n == 0;
+            }
+
+            fn main() {
+                var i = 1;
+                while (i <= 15) {
+                    if (divides(15, i)) {
+                        output(3);
+                    } else if (divides(3, i)) {
+                        output(1);
+                    } else if (divides(5, i)) {
+                        output(2);
+                    } else {
+                        output(0);
+                    }
+                    i = i + 1;
+                }
+            }";
+        assert_eq!(
+            run(source, &[]),
+            vec![0, 0, 1, 0, 2, 1, 0, 0, 1, 2, 0, 1, 0, 0, 3]
+        );
+    }
+
+    #[test]
+    fn test_recursion() {
+        let source = "
+            fn factorial(n) {
+                if (n <= 1) { return 1; }
+                return n * factorial(n - 1);
+            }
+
+            fn fibonacci(n) {
+                if (n < 2) { return n; }
+                return fibonacci(n - 1) + fibonacci(n - 2);
+            }
+
+            fn main() {
+                var n = input();
+                output(factorial(n));
+                output(fibonacci(n) + factorial(3) * fibonacci(factorial(3)));
+            }";
+        assert_eq!(run(source, &[10]), vec![3_628_800, 55 + 6 * 8]);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let source = "
+            fn main() {
+                var x = 1;
+                if (x) {
+                    var x = 2;
+                    output(x);
+                }
+                output(x);
+            }";
+        assert_eq!(run(source, &[]), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |source: &str| compile(source).unwrap_err();
+
+        assert_eq!(
+            error("fn helper() {}").message,
+            "Program has no main function"
+        );
+        assert_eq!(
+            error("fn main() {\n output(y);\n}"),
+            CompileError {
+                line: 2,
+                message: "Unknown variable y".to_string()
+            }
+        );
+        assert_eq!(
+            error("fn f(a) { return a; }\nfn main() { f(1, 2); }").message,
+            "f expects 1 arguments but was given 2"
+        );
+        assert_eq!(error("fn main() { var a = 1 / 2; }").line, 1);
+        assert_eq!(
+            error("fn main() { var a = 1; var a = 2; }").message,
+            "Variable a is already declared"
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use super::lexer::{Lexeme, Token};
+use super::CompileError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    Negate,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+}
+
+#[derive(Debug)]
+pub enum Expr {
+    Number(isize),
+    Variable {
+        name: String,
+        line: usize,
+    },
+    Call {
+        name: String,
+        args: Vec<Expr>,
+        line: usize,
+    },
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug)]
+pub enum Statement {
+    Var {
+        name: String,
+        value: Expr,
+        line: usize,
+    },
+    Assign {
+        name: String,
+        value: Expr,
+        line: usize,
+    },
+    If {
+        condition: Expr,
+        then: Vec<Statement>,
+        otherwise: Vec<Statement>,
+    },
+    While {
+        condition: Expr,
+        body: Vec<Statement>,
+    },
+    Return(Option<Expr>),
+    Expression(Expr),
+}
+
+#[derive(Debug)]
+pub struct Function {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub body: Vec<Statement>,
+    pub line: usize,
+}
+
+/// Recursive descent parser over a list of tokens
+struct Parser {
+    lexemes: Vec<Lexeme>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.lexemes[self.position].token
+    }
+
+    fn line(&self) -> usize {
+        self.lexemes[self.position].line
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.lexemes[self.position].token.clone();
+        if token != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, CompileError> {
+        Err(CompileError {
+            line: self.line(),
+            message,
+        })
+    }
+
+    /// Consume the expected token
+    fn expect(&mut self, expected: Token) -> Result<(), CompileError> {
+        if *self.peek() != expected {
+            return self.error(format!(
+                "Expected {:?} but found {:?}",
+                expected,
+                self.peek()
+            ));
+        }
+        self.next();
+        Ok(())
+    }
+
+    /// Consume the token if it matches
+    fn accept(&mut self, token: Token) -> bool {
+        if *self.peek() == token {
+            self.next();
+            return true;
+        }
+        false
+    }
+
+    fn name(&mut self) -> Result<String, CompileError> {
+        match self.peek().clone() {
+            Token::Name(name) => {
+                self.next();
+                Ok(name)
+            }
+            token => self.error(format!("Expected a name but found {:?}", token)),
+        }
+    }
+
+    fn function(&mut self) -> Result<Function, CompileError> {
+        let line = self.line();
+        self.expect(Token::Fn)?;
+        let name = self.name()?;
+
+        self.expect(Token::OpenParen)?;
+        let mut parameters = Vec::new();
+        if !self.accept(Token::CloseParen) {
+            loop {
+                parameters.push(self.name()?);
+                if self.accept(Token::CloseParen) {
+                    break;
+                }
+                self.expect(Token::Comma)?;
+            }
+        }
+
+        Ok(Function {
+            name,
+            parameters,
+            body: self.block()?,
+            line,
+        })
+    }
+
+    fn block(&mut self) -> Result<Vec<Statement>, CompileError> {
+        self.expect(Token::OpenBrace)?;
+        let mut statements = Vec::new();
+        while !self.accept(Token::CloseBrace) {
+            statements.push(self.statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Statement, CompileError> {
+        let line = self.line();
+        let statement = match self.peek() {
+            Token::Var => {
+                self.next();
+                let name = self.name()?;
+                self.expect(Token::Assign)?;
+                Statement::Var {
+                    name,
+                    value: self.expression()?,
+                    line,
+                }
+            }
+            Token::If => {
+                self.next();
+                let condition = self.condition()?;
+                let then = self.block()?;
+                let otherwise = if !self.accept(Token::Else) {
+                    Vec::new()
+                } else if *self.peek() == Token::If {
+                    vec![self.statement()?]
+                } else {
+                    self.block()?
+                };
+                return Ok(Statement::If {
+                    condition,
+                    then,
+                    otherwise,
+                });
+            }
+            Token::While => {
+                self.next();
+                let condition = self.condition()?;
+                return Ok(Statement::While {
+                    condition,
+                    body: self.block()?,
+                });
+            }
+            Token::Return => {
+                self.next();
+                if *self.peek() == Token::Semicolon {
+                    Statement::Return(None)
+                } else {
+                    Statement::Return(Some(self.expression()?))
+                }
+            }
+            Token::Name(name) if self.lexemes[self.position + 1].token == Token::Assign => {
+                let name = name.clone();
+                self.next();
+                self.next();
+                Statement::Assign {
+                    name,
+                    value: self.expression()?,
+                    line,
+                }
+            }
+            _ => Statement::Expression(self.expression()?),
+        };
+        self.expect(Token::Semicolon)?;
+        Ok(statement)
+    }
+
+    fn condition(&mut self) -> Result<Expr, CompileError> {
+        self.expect(Token::OpenParen)?;
+        let condition = self.expression()?;
+        self.expect(Token::CloseParen)?;
+        Ok(condition)
+    }
+
+    fn expression(&mut self) -> Result<Expr, CompileError> {
+        self.binary(0)
+    }
+
+    /// Parse binary operators by precedence level, lowest first
+    fn binary(&mut self, level: usize) -> Result<Expr, CompileError> {
+        const LEVELS: &[&[(Token, BinaryOp)]] = &[
+            &[(Token::Or, BinaryOp::Or)],
+            &[(Token::And, BinaryOp::And)],
+            &[
+                (Token::Equal, BinaryOp::Equal),
+                (Token::NotEqual, BinaryOp::NotEqual),
+            ],
+            &[
+                (Token::Less, BinaryOp::Less),
+                (Token::LessEqual, BinaryOp::LessEqual),
+                (Token::Greater, BinaryOp::Greater),
+                (Token::GreaterEqual, BinaryOp::GreaterEqual),
+            ],
+            &[
+                (Token::Plus, BinaryOp::Add),
+                (Token::Minus, BinaryOp::Subtract),
+            ],
+            &[(Token::Star, BinaryOp::Multiply)],
+        ];
+
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+
+        let mut left = self.binary(level + 1)?;
+        while let Some((_, op)) = LEVELS[level].iter().find(|(token, _)| token == self.peek()) {
+            self.next();
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(*op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, CompileError> {
+        if self.accept(Token::Minus) {
+            return Ok(match self.unary()? {
+                Expr::Number(value) => Expr::Number(-value),
+                expr => Expr::Unary(UnaryOp::Negate, Box::new(expr)),
+            });
+        }
+        if self.accept(Token::Not) {
+            return Ok(Expr::Unary(UnaryOp::Not, Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, CompileError> {
+        let line = self.line();
+        match self.next() {
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::OpenParen => {
+                let expr = self.expression()?;
+                self.expect(Token::CloseParen)?;
+                Ok(expr)
+            }
+            Token::Name(name) => {
+                if !self.accept(Token::OpenParen) {
+                    return Ok(Expr::Variable { name, line });
+                }
+                let mut args = Vec::new();
+                if !self.accept(Token::CloseParen) {
+                    loop {
+                        args.push(self.expression()?);
+                        if self.accept(Token::CloseParen) {
+                            break;
+                        }
+                        self.expect(Token::Comma)?;
+                    }
+                }
+                Ok(Expr::Call { name, args, line })
+            }
+            token => Err(CompileError {
+                line,
+                message: format!("Expected an expression but found {:?}", token),
+            }),
+        }
+    }
+}
+
+/// Parse a program, a list of functions
+pub fn parse(lexemes: Vec<Lexeme>) -> Result<Vec<Function>, CompileError> {
+    let mut parser = Parser {
+        lexemes,
+        position: 0,
+    };
+
+    let mut functions = Vec::new();
+    while *parser.peek() != Token::End {
+        functions.push(parser.function()?);
+    }
+    Ok(functions)
+}
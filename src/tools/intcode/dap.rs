@@ -0,0 +1,806 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::error;
+use std::fs;
+use std::io;
+use std::sync::mpsc;
+use std::thread;
+
+use serde_json::{json, Value};
+
+use crate::shared::intcode;
+
+/// Only a single thread is ever reported
+const THREAD_ID: i64 = 1;
+/// Variable references of the two scopes
+const REGISTERS: i64 = 1;
+const MEMORY: i64 = 2;
+/// Source reference of the program listing, line `n` of the listing is address `n - 1`
+const LISTING: i64 = 1;
+/// Instructions executed between checks for a pause request
+const POLL_INTERVAL: usize = 10_000;
+
+/// Read a single Content-Length framed message, returns None once the stream is closed
+fn read_message<R>(reader: &mut R) -> Result<Option<Value>, Box<dyn error::Error>>
+where
+    R: io::BufRead,
+{
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = Some(value.trim().parse::<usize>()?);
+        }
+    }
+
+    let mut body = vec![0; length.ok_or("Message is missing a Content-Length header")?];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+/// Requests read on their own thread, so a running program can still be paused
+struct Requests {
+    receiver: mpsc::Receiver<Result<Value, String>>,
+    /// requests that arrived while the program was running
+    pending: VecDeque<Result<Value, String>>,
+    closed: bool,
+}
+
+impl Requests {
+    fn spawn<R>(mut reader: R) -> Self
+    where
+        R: io::BufRead + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || loop {
+            let message = match read_message(&mut reader) {
+                Ok(Some(request)) => Ok(request),
+                Ok(None) => break,
+                Err(e) => Err(e.to_string()),
+            };
+            let failed = message.is_err();
+            if sender.send(message).is_err() || failed {
+                break;
+            }
+        });
+
+        Self {
+            receiver,
+            pending: VecDeque::new(),
+            closed: false,
+        }
+    }
+
+    /// Wait for the next request, returns None once the stream is closed
+    fn next(&mut self) -> Result<Option<Value>, Box<dyn error::Error>> {
+        let message = match self.pending.pop_front() {
+            Some(message) => message,
+            None => match self.receiver.recv() {
+                Ok(message) => message,
+                Err(_) => return Ok(None),
+            },
+        };
+        Ok(Some(message?))
+    }
+
+    /// Collect requests that arrived while running, returning a pause request if there is one
+    fn poll(&mut self) -> Option<Value> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Ok(request)) if request["command"] == "pause" => return Some(request),
+                Ok(message) => self.pending.push_back(message),
+                Err(mpsc::TryRecvError::Empty) => return None,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.closed = true;
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Has the client disconnected, or asked to, while the program was running
+    fn closing(&self) -> bool {
+        self.closed
+            || self.pending.iter().any(|message| match message {
+                Ok(request) => request["command"] == "disconnect",
+                Err(_) => true,
+            })
+    }
+}
+
+/// Sends responses and events to the client
+struct Adapter<W> {
+    writer: W,
+    seq: i64,
+}
+
+impl<W> Adapter<W>
+where
+    W: io::Write,
+{
+    fn new(writer: W) -> Self {
+        Self { writer, seq: 0 }
+    }
+
+    fn send(&mut self, mut message: Value) -> io::Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        let body = message.to_string();
+        write!(
+            self.writer,
+            "Content-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )?;
+        self.writer.flush()
+    }
+
+    fn respond(&mut self, request: &Value, body: Value) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": true,
+            "body": body,
+        }))
+    }
+
+    fn fail(&mut self, request: &Value, message: &str) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": false,
+            "message": message,
+        }))
+    }
+
+    fn event(&mut self, event: &str, body: Value) -> io::Result<()> {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }))
+    }
+
+    fn stopped(&mut self, reason: &str, text: Option<String>) -> io::Result<()> {
+        self.event(
+            "stopped",
+            json!({
+                "reason": reason,
+                "threadId": THREAD_ID,
+                "allThreadsStopped": true,
+                "text": text,
+            }),
+        )
+    }
+}
+
+/// Serve a debug session, the program is loaded by the launch request
+/// launch arguments are `program` (path), `inputs` (list of values) and `stopOnEntry`
+/// without a `program` the default program is debugged
+pub fn serve<R, W>(
+    reader: R,
+    writer: W,
+    default: Option<&HashMap<usize, isize>>,
+) -> Result<(), Box<dyn error::Error>>
+where
+    R: io::BufRead + Send + 'static,
+    W: io::Write,
+{
+    let mut adapter = Adapter::new(writer);
+    let mut requests = Requests::spawn(reader);
+
+    while let Some(request) = requests.next()? {
+        match request["command"].as_str().unwrap_or_default() {
+            "initialize" => adapter.respond(
+                &request,
+                json!({
+                    "supportsConfigurationDoneRequest": true,
+                    "supportsInstructionBreakpoints": true,
+                }),
+            )?,
+            "launch" => {
+                let arguments = &request["arguments"];
+                let mem = match arguments["program"].as_str() {
+                    Some(path) => match fs::File::open(path)
+                        .map_err(|e| e.into())
+                        .and_then(|file| intcode::parse_mem(io::BufReader::new(file)))
+                    {
+                        Ok(mem) => mem,
+                        Err(e) => {
+                            adapter.fail(&request, &format!("Unable to load {}: {}", path, e))?;
+                            continue;
+                        }
+                    },
+                    None => match default {
+                        Some(mem) => mem.clone(),
+                        None => {
+                            adapter.fail(&request, "Launch requires a program")?;
+                            continue;
+                        }
+                    },
+                };
+                let inputs = arguments["inputs"]
+                    .as_array()
+                    .map(|inputs| {
+                        inputs
+                            .iter()
+                            .filter_map(|input| input.as_i64())
+                            .map(|input| input as isize)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                adapter.respond(&request, json!({}))?;
+                adapter.event("initialized", json!({}))?;
+
+                let mut computer = intcode::Computer::new(&mem);
+                computer.enable_loop_detection(super::LOOP_DETECTION_CAPACITY);
+                let mut debuggee = Debuggee {
+                    computer,
+                    program: &mem,
+                    adapter,
+                    requests,
+                    inputs,
+                    breakpoints: HashSet::new(),
+                    instruction_breakpoints: HashSet::new(),
+                    stop_on_entry: arguments["stopOnEntry"].as_bool().unwrap_or(false),
+                    waiting: false,
+                    terminated: false,
+                };
+                return debuggee.serve();
+            }
+            "disconnect" => {
+                adapter.respond(&request, json!({}))?;
+                return Ok(());
+            }
+            _ => adapter.fail(&request, "Program has not been launched")?,
+        }
+    }
+    Ok(())
+}
+
+/// Launched program being debugged
+struct Debuggee<'a, W> {
+    computer: intcode::Computer<'a>,
+    program: &'a HashMap<usize, isize>,
+    adapter: Adapter<W>,
+    requests: Requests,
+    inputs: VecDeque<isize>,
+    /// source breakpoints on the program listing, by address
+    breakpoints: HashSet<usize>,
+    instruction_breakpoints: HashSet<usize>,
+    stop_on_entry: bool,
+    /// program is waiting for an input
+    waiting: bool,
+    terminated: bool,
+}
+
+impl<'a, W> Debuggee<'a, W>
+where
+    W: io::Write,
+{
+    fn serve(&mut self) -> Result<(), Box<dyn error::Error>> {
+        while let Some(request) = self.requests.next()? {
+            let arguments = &request["arguments"];
+            match request["command"].as_str().unwrap_or_default() {
+                "setBreakpoints" => {
+                    let (addresses, breakpoints) =
+                        breakpoints(&arguments["breakpoints"], |breakpoint| {
+                            usize::try_from(breakpoint["line"].as_u64()?)
+                                .ok()?
+                                .checked_sub(1)
+                        });
+                    self.breakpoints = addresses;
+                    self.adapter
+                        .respond(&request, json!({ "breakpoints": breakpoints }))?;
+                }
+                "setInstructionBreakpoints" => {
+                    let (addresses, breakpoints) =
+                        breakpoints(&arguments["breakpoints"], |breakpoint| {
+                            let address = breakpoint["instructionReference"]
+                                .as_str()?
+                                .parse::<i64>()
+                                .ok()?;
+                            let offset = breakpoint["offset"].as_i64().unwrap_or(0);
+                            usize::try_from(address.checked_add(offset)?).ok()
+                        });
+                    self.instruction_breakpoints = addresses;
+                    self.adapter
+                        .respond(&request, json!({ "breakpoints": breakpoints }))?;
+                }
+                "configurationDone" => {
+                    self.adapter.respond(&request, json!({}))?;
+                    if self.stop_on_entry {
+                        self.adapter.stopped("entry", None)?;
+                    } else {
+                        self.execute(false, false)?;
+                    }
+                }
+                "threads" => self.adapter.respond(
+                    &request,
+                    json!({"threads": [{"id": THREAD_ID, "name": "intcode"}]}),
+                )?,
+                "stackTrace" => {
+                    let address = self.computer.main_pointer();
+                    let code = *self.computer.ram.get(&address).unwrap_or(&0) as usize % 100;
+                    let name = self
+                        .computer
+                        .instruction_set()
+                        .get(code)
+                        .map_or("?", |instruction| instruction.name());
+                    self.adapter.respond(
+                        &request,
+                        json!({
+                            "stackFrames": [{
+                                "id": 0,
+                                "name": format!("{} @ {}", name, address),
+                                "source": {"name": "Intcode Listing", "sourceReference": LISTING},
+                                "line": address + 1,
+                                "column": 1,
+                                "instructionPointerReference": address.to_string(),
+                            }],
+                            "totalFrames": 1,
+                        }),
+                    )?;
+                }
+                "scopes" => self.adapter.respond(
+                    &request,
+                    json!({"scopes": [
+                        {"name": "Registers", "variablesReference": REGISTERS, "expensive": false},
+                        {
+                            "name": "Memory",
+                            "variablesReference": MEMORY,
+                            "indexedVariables": self.computer.ram.len(),
+                            "expensive": true,
+                        },
+                    ]}),
+                )?,
+                "variables" => {
+                    let variables = self.variables(arguments);
+                    self.adapter
+                        .respond(&request, json!({ "variables": variables }))?;
+                }
+                "continue" | "next" | "stepIn" => {
+                    if self.terminated {
+                        self.adapter.fail(&request, "Program has halted")?;
+                        continue;
+                    }
+                    let single = request["command"] != "continue";
+                    self.adapter
+                        .respond(&request, json!({"allThreadsContinued": true}))?;
+                    self.execute(single, true)?;
+                }
+                "source" => match arguments["sourceReference"].as_i64() {
+                    Some(LISTING) => self
+                        .adapter
+                        .respond(&request, json!({ "content": self.listing() }))?,
+                    _ => self.adapter.fail(&request, "Unknown source")?,
+                },
+                // a running program is paused while it executes, so it is already stopped here
+                "pause" => self.adapter.respond(&request, json!({}))?,
+                "evaluate" => {
+                    let expression = arguments["expression"].as_str().unwrap_or_default();
+                    match self.evaluate(expression.trim()) {
+                        Ok(result) => self.adapter.respond(
+                            &request,
+                            json!({"result": result, "variablesReference": 0}),
+                        )?,
+                        Err(message) => self.adapter.fail(&request, &message)?,
+                    }
+                }
+                "disconnect" => {
+                    self.adapter.respond(&request, json!({}))?;
+                    return Ok(());
+                }
+                _ => self.adapter.fail(&request, "Unsupported request")?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Program as loaded, one cell per line so each line maps to an address
+    fn listing(&self) -> String {
+        let end = self.program.keys().max().map_or(0, |address| address + 1);
+        (0..end)
+            .map(|address| {
+                format!(
+                    "{:>6}: {}",
+                    address,
+                    self.program.get(&address).unwrap_or(&0)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Registers or a page of memory cells ordered by address
+    fn variables(&self, arguments: &Value) -> Vec<Value> {
+        let variable = |name: String, value: String| json!({"name": name, "value": value, "variablesReference": 0});
+
+        match arguments["variablesReference"].as_i64() {
+            Some(REGISTERS) => vec![
+                variable(
+                    "main_pointer".to_string(),
+                    self.computer.main_pointer().to_string(),
+                ),
+                variable(
+                    "relative_pointer".to_string(),
+                    self.computer.relative_pointer().to_string(),
+                ),
+                variable(
+                    "instruction_count".to_string(),
+                    self.computer.instruction_count().to_string(),
+                ),
+            ],
+            Some(MEMORY) => {
+                let mut cells: Vec<(&usize, &isize)> = self.computer.ram.iter().collect();
+                cells.sort();
+                let start = arguments["start"].as_u64().unwrap_or(0) as usize;
+                let count = arguments["count"]
+                    .as_u64()
+                    .map_or(cells.len(), |c| c as usize);
+                cells
+                    .into_iter()
+                    .skip(start)
+                    .take(count)
+                    .map(|(address, value)| variable(format!("[{}]", address), value.to_string()))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// A number is queued as an input, `[address]` reads memory
+    fn evaluate(&mut self, expression: &str) -> Result<String, String> {
+        if let Some(address) = expression
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            let address: usize = address
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid address {}: {}", address, e))?;
+            return Ok(self.computer.ram.get(&address).unwrap_or(&0).to_string());
+        }
+
+        let input: isize = expression.parse().map_err(|_| {
+            format!(
+                "Expected an input value or [address] but got {}",
+                expression
+            )
+        })?;
+        self.inputs.push_back(input);
+        Ok(format!("Input {} queued", input))
+    }
+
+    /// Execute until a breakpoint, input is needed, the program halts or the client pauses it
+    /// a single step stops after one instruction, loops that never reach I/O stop as an exception
+    fn execute(&mut self, single: bool, resuming: bool) -> io::Result<()> {
+        let mut first = resuming;
+        for executed in 1.. {
+            if executed % POLL_INTERVAL == 0 {
+                if let Some(request) = self.requests.poll() {
+                    self.adapter.respond(&request, json!({}))?;
+                    return self.adapter.stopped("pause", None);
+                }
+                // the disconnect is handled once back to serving requests
+                if self.requests.closing() {
+                    return Ok(());
+                }
+            }
+
+            if self.waiting {
+                match self.inputs.pop_front() {
+                    Some(input) => {
+                        if let Err(e) = self.computer.send_input(input) {
+                            return self.adapter.stopped("exception", Some(e.to_string()));
+                        }
+                        self.waiting = false;
+                    }
+                    None => {
+                        return self.adapter.stopped(
+                            "pause",
+                            Some("Waiting for input, evaluate a value to send it".to_string()),
+                        )
+                    }
+                }
+            }
+
+            let address = self.computer.main_pointer();
+            if !first
+                && (self.breakpoints.contains(&address)
+                    || self.instruction_breakpoints.contains(&address))
+            {
+                return self.adapter.stopped("breakpoint", None);
+            }
+            first = false;
+
+            match self.computer.step() {
+                Ok(Some(intcode::HaltedState::Output(value))) => self.adapter.event(
+                    "output",
+                    json!({"category": "stdout", "output": format!("{}\n", value)}),
+                )?,
+                Ok(Some(intcode::HaltedState::Input)) => self.waiting = true,
+                Ok(Some(intcode::HaltedState::Halt)) => {
+                    self.terminated = true;
+                    self.adapter.event("exited", json!({"exitCode": 0}))?;
+                    return self.adapter.event("terminated", json!({}));
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    self.adapter.event(
+                        "output",
+                        json!({"category": "stderr", "output": format!("{}\n", e)}),
+                    )?;
+                    return self.adapter.stopped("exception", Some(e.to_string()));
+                }
+            }
+
+            if single {
+                return self.adapter.stopped("step", None);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Collect breakpoint addresses from a list of breakpoints, along with the breakpoints to report back
+/// breakpoints that do not give a valid address are reported as unverified
+fn breakpoints<F>(breakpoints: &Value, address: F) -> (HashSet<usize>, Vec<Value>)
+where
+    F: Fn(&Value) -> Option<usize>,
+{
+    let mut addresses = HashSet::new();
+    let mut reported = Vec::new();
+    for breakpoint in breakpoints.as_array().into_iter().flatten() {
+        let mut report = match address(breakpoint) {
+            Some(address) => {
+                addresses.insert(address);
+                json!({"verified": true})
+            }
+            None => json!({"verified": false, "message": "Not a valid address"}),
+        };
+        if !breakpoint["line"].is_null() {
+            report["line"] = breakpoint["line"].clone();
+        }
+        reported.push(report);
+    }
+    (addresses, reported)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// outputs 1 if the input is zero, otherwise 2
+    const PROGRAM: &str = "3,13,1006,13,9,104,2,99,0,104,1,99,0,0";
+
+    /// Serve framed requests, returning every message sent back
+    fn session(mem: &HashMap<usize, isize>, requests: &[Value]) -> Vec<Value> {
+        let mut bytes = Vec::new();
+        for (seq, request) in requests.iter().enumerate() {
+            let mut request = request.clone();
+            request["seq"] = json!(seq + 1);
+            request["type"] = json!("request");
+            let body = request.to_string();
+            bytes.extend(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).bytes());
+        }
+
+        let mut output = Vec::new();
+        serve(io::Cursor::new(bytes), &mut output, Some(mem)).unwrap();
+
+        let mut reader = output.as_slice();
+        let mut messages = Vec::new();
+        while let Some(message) = read_message(&mut reader).unwrap() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    /// Events sent, with the reason a program stopped or the text it output
+    fn events(messages: &[Value]) -> Vec<String> {
+        messages
+            .iter()
+            .filter(|message| message["type"] == "event")
+            .map(|message| match message["event"].as_str().unwrap() {
+                "stopped" => format!("stopped {}", message["body"]["reason"].as_str().unwrap()),
+                "output" => format!("output {}", message["body"]["output"].as_str().unwrap()),
+                event => event.to_string(),
+            })
+            .collect()
+    }
+
+    fn response<'m>(messages: &'m [Value], command: &str) -> &'m Value {
+        messages
+            .iter()
+            .find(|message| message["command"] == command)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_session() {
+        let mem = intcode::parse_mem(PROGRAM.as_bytes()).unwrap();
+        let messages = session(
+            &mem,
+            &[
+                json!({"command": "initialize", "arguments": {}}),
+                json!({"command": "launch", "arguments": {"stopOnEntry": true}}),
+                json!({"command": "setInstructionBreakpoints", "arguments": {
+                    "breakpoints": [{"instructionReference": "9"}]
+                }}),
+                json!({"command": "configurationDone"}),
+                json!({"command": "next"}),
+                json!({"command": "continue"}),
+                json!({"command": "evaluate", "arguments": {"expression": "0"}}),
+                json!({"command": "continue"}),
+                json!({"command": "variables", "arguments": {"variablesReference": REGISTERS}}),
+                json!({"command": "evaluate", "arguments": {"expression": "[13]"}}),
+                json!({"command": "continue"}),
+                json!({"command": "disconnect"}),
+            ],
+        );
+        assert!(messages
+            .iter()
+            .all(|message| message["type"] == "event" || message["success"] == true));
+
+        assert_eq!(
+            events(&messages),
+            vec![
+                "initialized",
+                "stopped entry",
+                "stopped step",
+                "stopped pause",
+                "stopped breakpoint",
+                "output 1\n",
+                "exited",
+                "terminated",
+            ]
+        );
+
+        let variables = response(&messages, "variables");
+        assert_eq!(variables["body"]["variables"][0]["value"], "9");
+        let memory = messages
+            .iter()
+            .filter(|message| message["command"] == "evaluate")
+            .nth(1)
+            .unwrap();
+        assert_eq!(memory["body"]["result"], "0");
+    }
+
+    #[test]
+    fn test_listing_lines() {
+        // line 10 of the listing is address 9
+        let mem = intcode::parse_mem(PROGRAM.as_bytes()).unwrap();
+        let messages = session(
+            &mem,
+            &[
+                json!({"command": "launch", "arguments": {"inputs": [0]}}),
+                json!({"command": "setBreakpoints", "arguments": {
+                    "source": {"sourceReference": LISTING},
+                    "breakpoints": [{"line": 10}]
+                }}),
+                json!({"command": "configurationDone"}),
+                json!({"command": "stackTrace", "arguments": {"threadId": THREAD_ID}}),
+                json!({"command": "source", "arguments": {"sourceReference": LISTING}}),
+                json!({"command": "disconnect"}),
+            ],
+        );
+        assert_eq!(events(&messages), vec!["initialized", "stopped breakpoint"]);
+
+        let frame = &response(&messages, "stackTrace")["body"]["stackFrames"][0];
+        assert_eq!(frame["line"], 10);
+        assert_eq!(frame["instructionPointerReference"], "9");
+
+        let content = response(&messages, "source")["body"]["content"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 14);
+        assert_eq!(lines[9], "     9: 104");
+    }
+
+    #[test]
+    fn test_invalid_breakpoints() {
+        let mem = intcode::parse_mem(PROGRAM.as_bytes()).unwrap();
+        let messages = session(
+            &mem,
+            &[
+                json!({"command": "launch", "arguments": {}}),
+                json!({"command": "setInstructionBreakpoints", "arguments": {
+                    "breakpoints": [
+                        {"instructionReference": "9223372036854775807", "offset": 1},
+                        {"instructionReference": "2", "offset": -3},
+                        {"instructionReference": "x"},
+                        {"instructionReference": "7", "offset": 2},
+                    ]
+                }}),
+                json!({"command": "setBreakpoints", "arguments": {
+                    "breakpoints": [{"line": 0}, {"line": 3}]
+                }}),
+                json!({"command": "disconnect"}),
+            ],
+        );
+
+        let verified = |command: &str| -> Vec<bool> {
+            response(&messages, command)["body"]["breakpoints"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|breakpoint| breakpoint["verified"].as_bool().unwrap())
+                .collect()
+        };
+        assert_eq!(
+            verified("setInstructionBreakpoints"),
+            vec![false, false, false, true]
+        );
+        assert_eq!(verified("setBreakpoints"), vec![false, true]);
+    }
+
+    #[test]
+    fn test_failed_launch() {
+        let path = std::env::temp_dir().join("intcode_dap_invalid_program.txt");
+        fs::write(&path, "1,2,x").unwrap();
+
+        let mem = intcode::parse_mem(PROGRAM.as_bytes()).unwrap();
+        let messages = session(
+            &mem,
+            &[
+                json!({"command": "launch", "arguments": {"program": path}}),
+                json!({"command": "launch", "arguments": {"stopOnEntry": true}}),
+                json!({"command": "configurationDone"}),
+                json!({"command": "disconnect"}),
+            ],
+        );
+        fs::remove_file(&path).unwrap();
+
+        let launches: Vec<&Value> = messages
+            .iter()
+            .filter(|message| message["command"] == "launch")
+            .collect();
+        assert_eq!(launches[0]["success"], false);
+        assert_eq!(launches[1]["success"], true);
+        assert_eq!(events(&messages), vec!["initialized", "stopped entry"]);
+    }
+
+    #[test]
+    fn test_runaway_programs() {
+        // jumps to itself forever
+        let mem = intcode::parse_mem("1105,1,0".as_bytes()).unwrap();
+        let messages = session(
+            &mem,
+            &[
+                json!({"command": "launch", "arguments": {}}),
+                json!({"command": "configurationDone"}),
+                json!({"command": "disconnect"}),
+            ],
+        );
+        let looping = events(&messages);
+        assert_eq!(looping.len(), 3);
+        assert!(looping[1].starts_with("output Program is looping"));
+        assert_eq!(looping[2], "stopped exception");
+
+        // counts up forever, so never revisits a state
+        let mem = intcode::parse_mem("1001,7,1,7,1105,1,0,0".as_bytes()).unwrap();
+        let messages = session(
+            &mem,
+            &[
+                json!({"command": "launch", "arguments": {}}),
+                json!({"command": "configurationDone"}),
+                json!({"command": "pause", "arguments": {"threadId": THREAD_ID}}),
+                json!({"command": "disconnect"}),
+            ],
+        );
+        assert_eq!(events(&messages), vec!["initialized", "stopped pause"]);
+        assert_eq!(response(&messages, "pause")["success"], true);
+    }
+}
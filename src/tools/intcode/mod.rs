@@ -0,0 +1,376 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error;
+use std::fs;
+use std::io::{self, Write};
+use std::net;
+use std::rc::Rc;
+
+use itertools::Itertools;
+
+use crate::shared::intcode;
+use crate::shared::intcode::ascii;
+use crate::shared::intcode::coverage;
+use crate::shared::intcode::session;
+
+pub mod compiler;
+mod dap;
+pub mod optimizer;
+
+/// Machine states remembered while looking for infinite loops
+const LOOP_DETECTION_CAPACITY: usize = 1_000_000;
+
+/// Cells per row of a memory dump
+const DUMP_COLUMNS: usize = 8;
+
+/// Run a program interactively, lines are read from input and text is written to output
+/// any non ASCII values are returned as the result
+pub fn ascii<T, I, O>(
+    reader: T,
+    mut input: I,
+    mut output: O,
+) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+    I: io::BufRead,
+    O: io::Write,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let mut terminal = ascii::Terminal::new(intcode::Computer::new(&mem));
+
+    let mut values = Vec::new();
+    loop {
+        match terminal.run()? {
+            ascii::AsciiState::Line(line) => writeln!(output, "{}", line)?,
+            ascii::AsciiState::Value(value) => values.push(value),
+            ascii::AsciiState::Input => {
+                output.flush()?;
+                let mut line = String::new();
+                if input.read_line(&mut line)? == 0 {
+                    return Err("Program requested input but no more input is available".into());
+                }
+                terminal.send_line(line.trim_end_matches(&['\r', '\n'][..]))?;
+            }
+            ascii::AsciiState::Halt => break,
+        }
+    }
+
+    if values.is_empty() {
+        return Ok("Intcode ASCII Program Halted".to_string());
+    }
+    Ok(format!("Intcode ASCII Result: {}", values.iter().join(",")))
+}
+
+/// Parse a list of input values separated by commas or whitespace
+fn parse_inputs(inputs: &str) -> Result<Vec<isize>, Box<dyn error::Error>> {
+    let mut values = Vec::new();
+    for token in inputs
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+    {
+        values.push(
+            token
+                .parse()
+                .map_err(|e| format!("Unable to parse input {}: {}", token, e))?,
+        );
+    }
+    Ok(values)
+}
+
+/// Options for `run`
+#[derive(Default)]
+pub struct RunOptions {
+    /// inputs sent in order as the program requests them
+    pub inputs: Vec<isize>,
+    /// file of further inputs, sent after `inputs`
+    pub inputs_file: Option<String>,
+    /// memory patches applied before running
+    pub pokes: Vec<(usize, isize)>,
+    /// memory cells to report once the program halts
+    pub peeks: Vec<usize>,
+    /// report the final pointers and any unused inputs
+    pub state: bool,
+    /// file to record the I/O session to
+    pub record: Option<String>,
+    /// report a dump of memory once the program halts
+    pub dump_after: bool,
+    /// show the memory dump in hexadecimal with the cells read as ASCII
+    pub hex: bool,
+    /// report the cells changed from the loaded program
+    pub diff_rom: bool,
+}
+
+/// Run a program with scripted inputs and memory patches
+/// all outputs are reported, along with the final state and memory if requested
+/// the I/O session is written to the record file even when the run fails
+/// infinite loops are reported as an error rather than hanging
+pub fn run<T>(reader: T, options: RunOptions) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let mut inputs = options.inputs;
+    if let Some(filename) = options.inputs_file {
+        inputs.extend(parse_inputs(&fs::read_to_string(filename)?)?);
+    }
+
+    let mut computer = intcode::Computer::new(&mem);
+    computer.enable_loop_detection(LOOP_DETECTION_CAPACITY);
+    let mut recorder = session::Recorder::new(&mut computer);
+    for (address, value) in options.pokes {
+        recorder.poke(address, value);
+    }
+    let mut inputs = inputs.into_iter();
+    let mut outputs = Vec::new();
+    let result = loop {
+        match recorder.run() {
+            Ok(intcode::HaltedState::Halt) => break Ok(()),
+            Ok(intcode::HaltedState::Input) => match inputs.next() {
+                Some(input) => {
+                    if let Err(e) = recorder.send_input(input) {
+                        break Err(e);
+                    }
+                }
+                None => break Err("Program requested input but no inputs remain".into()),
+            },
+            Ok(intcode::HaltedState::Output(output)) => outputs.push(output),
+            Err(e) => break Err(e),
+        }
+    };
+    if let Some(filename) = options.record {
+        recorder
+            .session()
+            .write(io::BufWriter::new(fs::File::create(filename)?))?;
+    }
+    result?;
+
+    let mut report = vec![format!("Intcode Run Outputs: {}", outputs.iter().join(","))];
+    if options.state {
+        report.push(format!("Main Pointer: {}", computer.main_pointer()));
+        report.push(format!("Relative Pointer: {}", computer.relative_pointer()));
+        report.push(format!("Unused Inputs: {}", inputs.join(",")));
+    }
+    for address in options.peeks {
+        report.push(format!(
+            "Memory[{}]: {}",
+            address,
+            computer.ram.get(&address).unwrap_or(&0)
+        ));
+    }
+    if options.diff_rom {
+        let changes = computer.diff_rom();
+        report.push(format!("Memory Changes: {}", changes.len()));
+        report.extend(changes.iter().map(|change| change.to_string()));
+    }
+    if options.dump_after {
+        let end = computer.ram.keys().max().map_or(0, |address| address + 1);
+        report.push("Memory Dump:".to_string());
+        report.push(if options.hex {
+            computer.hex_dump(0..end, DUMP_COLUMNS)
+        } else {
+            computer.dump(0..end, DUMP_COLUMNS)
+        });
+    }
+    Ok(report.join("\n"))
+}
+
+/// Write the program out as a binary memory image for fast reloading
+pub fn image<T>(reader: T, output: String) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let writer = io::BufWriter::new(fs::File::create(&output)?);
+    intcode::loader::write_image(&mem, writer)?;
+
+    Ok(format!(
+        "Intcode Image Written: {} cells to {}",
+        mem.len(),
+        output
+    ))
+}
+
+/// Comma separated program text, with any gaps in memory filled by zeros
+fn program_text(mem: &HashMap<usize, isize>) -> String {
+    let end = mem.keys().max().map_or(0, |address| address + 1);
+    (0..end)
+        .map(|address| mem.get(&address).copied().unwrap_or(0))
+        .join(",")
+}
+
+/// Write a program as a binary memory image or as comma separated text
+fn write_program(
+    mem: &HashMap<usize, isize>,
+    output: &str,
+    text: bool,
+) -> Result<(), Box<dyn error::Error>> {
+    let mut writer = io::BufWriter::new(fs::File::create(output)?);
+    if text {
+        writeln!(writer, "{}", program_text(mem))?;
+    } else {
+        intcode::loader::write_image(mem, writer)?;
+    }
+    Ok(())
+}
+
+/// Compile a program written in the Intcode language, writing it as a memory image
+/// or as comma separated text
+pub fn compile<T>(
+    mut reader: T,
+    output: String,
+    text: bool,
+) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mut source = String::new();
+    reader.read_to_string(&mut source)?;
+    let mem = compiler::compile(&source)?;
+
+    write_program(&mem, &output, text)?;
+
+    Ok(format!(
+        "Intcode Compiled: {} cells to {}",
+        mem.len(),
+        output
+    ))
+}
+
+/// Replay a recorded session, verifying the program produces the same outputs
+pub fn replay<T>(reader: T, session: String) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let session = session::Session::parse(io::BufReader::new(fs::File::open(session)?))?;
+
+    let mut computer = intcode::Computer::new(&mem);
+    let events = session::replay(&mut computer, &session)?;
+
+    Ok(format!(
+        "Intcode Replay Matched: {} events over {} instructions",
+        events,
+        computer.instruction_count()
+    ))
+}
+
+/// Run a program with scripted inputs, reporting coverage of its instructions, branches and memory
+/// an annotated listing of the program follows the summary if requested
+pub fn coverage<T>(
+    reader: T,
+    mut inputs: Vec<isize>,
+    inputs_file: Option<String>,
+    listing: bool,
+) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    if let Some(filename) = inputs_file {
+        inputs.extend(parse_inputs(&fs::read_to_string(filename)?)?);
+    }
+
+    let mut computer = intcode::Computer::new(&mem);
+    let coverage = Rc::new(RefCell::new(coverage::Coverage::new(
+        computer.instruction_set(),
+    )));
+    computer.enable_loop_detection(LOOP_DETECTION_CAPACITY);
+    computer.set_hook(Box::new(Rc::clone(&coverage)));
+
+    let mut inputs = inputs.into_iter();
+    loop {
+        match computer.run()? {
+            intcode::HaltedState::Halt => break,
+            intcode::HaltedState::Input => match inputs.next() {
+                Some(input) => computer.send_input(input)?,
+                None => return Err("Program requested input but no inputs remain".into()),
+            },
+            intcode::HaltedState::Output(_) => (),
+        }
+    }
+
+    let coverage = coverage.borrow();
+    let mut report = format!("Intcode Coverage:\n{}", coverage.summary(&mem));
+    if listing {
+        report.push('\n');
+        report.push_str(&coverage.listing(&mem, computer.instruction_set()));
+    }
+    Ok(report)
+}
+
+/// Serve a Debug Adapter Protocol session, over stdio or a single connection on a local TCP port
+/// when serving over TCP the program read from input is debugged unless launch names another,
+/// over stdio launch must name the program and the summary is only written once the session ends
+pub fn dap<T>(reader: T, port: Option<u16>) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    match port {
+        Some(port) => {
+            let mem = intcode::parse_mem(reader)?;
+            let listener = net::TcpListener::bind(("127.0.0.1", port))?;
+            let (stream, client) = listener.accept()?;
+            dap::serve(io::BufReader::new(stream.try_clone()?), stream, Some(&mem))?;
+            Ok(format!("Intcode Debug Session Ended: served {}", client))
+        }
+        None => {
+            dap::serve(io::BufReader::new(io::stdin()), io::stdout(), None)?;
+            Ok("Intcode Debug Session Ended".to_string())
+        }
+    }
+}
+
+/// Optimize a program and write it out, the optimized program is checked against the original
+/// using the inputs of each recorded session and the listed inputs
+pub fn optimize<T>(
+    reader: T,
+    output: String,
+    text: bool,
+    sessions: Vec<String>,
+    inputs: Vec<isize>,
+) -> Result<String, Box<dyn error::Error>>
+where
+    T: io::BufRead,
+{
+    let mem = intcode::parse_mem(reader)?;
+    let (optimized, report) = optimizer::optimize(&mem)?;
+
+    let mut input_sets = vec![inputs];
+    for filename in sessions {
+        let session = session::Session::parse(io::BufReader::new(fs::File::open(filename)?))?;
+        input_sets.push(
+            session
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    session::Event::Input { value, .. } => Some(*value),
+                    session::Event::Output { .. } => None,
+                })
+                .collect(),
+        );
+    }
+    optimizer::verify(&mem, &optimized, &input_sets)?;
+    write_program(&optimized, &output, text)?;
+
+    Ok(format!(
+        "Intcode Optimized: {}\nVerified against {} input sets, {} cells written to {}",
+        report,
+        input_sets.len(),
+        optimized.len(),
+        output
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_program_text() {
+        let mem: HashMap<usize, isize> = vec![(0, 1101), (1, -2), (4, 99), (6, 7)]
+            .into_iter()
+            .collect();
+        assert_eq!(program_text(&mem), "1101,-2,0,0,99,0,7");
+        assert_eq!(program_text(&HashMap::new()), "");
+    }
+}
@@ -0,0 +1,682 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use crate::shared::intcode::{self, instructions, HaltedState, Parameter};
+
+const ADD: isize = 1;
+const MULTIPLY: isize = 2;
+const JUMP_IF_TRUE: isize = 5;
+const JUMP_IF_FALSE: isize = 6;
+const LESS_THAN: isize = 7;
+const EQUALS: isize = 8;
+const HALT: isize = 99;
+
+const POSITION: isize = 0;
+const IMMEDIATE: isize = 1;
+
+/// Machine states remembered while verifying, so a looping program fails rather than hangs
+const LOOP_DETECTION_CAPACITY: usize = 1_000_000;
+
+/// Decoded instruction
+#[derive(Debug, Clone)]
+struct Instruction {
+    code: isize,
+    modes: Vec<isize>,
+    operands: Vec<isize>,
+    parameters: Vec<Parameter>,
+}
+
+impl Instruction {
+    fn width(&self) -> usize {
+        self.operands.len() + 1
+    }
+
+    fn is_jump(&self) -> bool {
+        self.code == JUMP_IF_TRUE || self.code == JUMP_IF_FALSE
+    }
+
+    /// Is a parameter a position mode read of data, jump targets are tracked separately
+    fn reads(&self, index: usize) -> bool {
+        self.parameters[index] == Parameter::Read
+            && self.modes[index] == POSITION
+            && !(self.is_jump() && index == 1)
+    }
+
+    /// Whether a jump is taken, when its condition is a constant
+    fn taken(&self) -> Option<bool> {
+        if !self.is_jump() || self.modes[0] != IMMEDIATE {
+            return None;
+        }
+        Some((self.operands[0] != 0) == (self.code == JUMP_IF_TRUE))
+    }
+
+    /// Value written by an arithmetic or comparison instruction of two constants,
+    /// unless the arithmetic overflows
+    fn constant(&self) -> Option<isize> {
+        if self.modes[..self.modes.len().min(2)] != [IMMEDIATE, IMMEDIATE] {
+            return None;
+        }
+        let (a, b) = (self.operands[0], self.operands[1]);
+        match self.code {
+            ADD => a.checked_add(b),
+            MULTIPLY => a.checked_mul(b),
+            LESS_THAN => Some((a < b) as isize),
+            EQUALS => Some((a == b) as isize),
+            _ => None,
+        }
+    }
+
+    /// Does the instruction leave memory unchanged and fall through (`add x, 0, x` or `mul x, 1, x`)
+    fn is_noop(&self) -> bool {
+        let identity = match self.code {
+            ADD => 0,
+            MULTIPLY => 1,
+            _ => return self.taken() == Some(false),
+        };
+        let destination = self.operands[2];
+        (0..2).any(|index| {
+            let other = 1 - index;
+            self.modes[index] == IMMEDIATE
+                && self.operands[index] == identity
+                && self.modes[other] == POSITION
+                && self.operands[other] == destination
+        })
+    }
+
+    fn encode(&self) -> Vec<isize> {
+        let modes: isize = self
+            .modes
+            .iter()
+            .enumerate()
+            .map(|(index, mode)| mode * 10_isize.pow(index as u32 + 2))
+            .sum();
+        let mut cells = vec![self.code + modes];
+        cells.extend(&self.operands);
+        cells
+    }
+}
+
+/// Decode the instruction at an address, only constant addressing can be analyzed
+fn decode(mem: &HashMap<usize, isize>, address: usize) -> Result<Instruction, String> {
+    let value = *mem
+        .get(&address)
+        .ok_or_else(|| format!("Execution reaches address {} outside the program", address))?;
+    if value < 0 {
+        return Err(format!(
+            "Invalid instruction {} at address {}",
+            value, address
+        ));
+    }
+
+    let code = value % 100;
+    let standard = instructions::standard();
+    let instruction = standard
+        .get(code as usize)
+        .ok_or_else(|| format!("Unknown opcode {} at address {}", code, address))?;
+
+    let mut modes = Vec::new();
+    let mut operands = Vec::new();
+    let mut mode_digits = value / 100;
+    for (index, parameter) in instruction.parameters().iter().enumerate() {
+        let mode = mode_digits % 10;
+        mode_digits /= 10;
+        match (mode, parameter) {
+            (POSITION, _) | (IMMEDIATE, Parameter::Read) => (),
+            (IMMEDIATE, Parameter::Write) => {
+                return Err(format!("Immediate write at address {}", address))
+            }
+            _ => {
+                return Err(format!(
+                    "Relative addressing at address {} cannot be analyzed",
+                    address
+                ))
+            }
+        }
+        modes.push(mode);
+        operands.push(*mem.get(&(address + index + 1)).unwrap_or(&0));
+    }
+
+    Ok(Instruction {
+        code,
+        modes,
+        operands,
+        parameters: instruction.parameters().to_vec(),
+    })
+}
+
+/// Summary of the rewrites made
+#[derive(Debug, Default, PartialEq)]
+pub struct Report {
+    pub folded_reads: usize,
+    pub removed_instructions: usize,
+    pub removed_cells: usize,
+    pub shortened_jumps: usize,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Folded {} constant reads, removed {} instructions ({} cells), shortened {} jumps",
+            self.folded_reads, self.removed_instructions, self.removed_cells, self.shortened_jumps
+        )
+    }
+}
+
+/// Program proven to have a fixed layout
+/// every instruction is reachable from a known jump, no code is ever read or written as data
+/// and every jump target is a constant
+struct Program<'m> {
+    mem: &'m HashMap<usize, isize>,
+    instructions: BTreeMap<usize, Instruction>,
+    /// never written cells holding the target of a position mode jump
+    target_cells: HashSet<usize>,
+    /// address to the instructions writing it
+    writers: HashMap<usize, Vec<usize>>,
+    removed: HashSet<usize>,
+}
+
+impl<'m> Program<'m> {
+    fn analyze(mem: &'m HashMap<usize, isize>) -> Result<Self, String> {
+        let mut program = Program {
+            mem,
+            instructions: BTreeMap::new(),
+            target_cells: HashSet::new(),
+            writers: HashMap::new(),
+            removed: HashSet::new(),
+        };
+
+        // a decoding failure is often caused by self modifying code,
+        // so the code decoded so far is checked before it is reported
+        let mut failure = None;
+        let mut pending = vec![0];
+        while let Some(address) = pending.pop() {
+            if program.instructions.contains_key(&address) {
+                continue;
+            }
+            match decode(mem, address) {
+                Ok(instruction) => {
+                    program.instructions.insert(address, instruction);
+                    pending.extend(program.successors(address));
+                }
+                Err(e) => {
+                    failure = Some((address, e));
+                    break;
+                }
+            }
+        }
+
+        let mut owners = HashMap::new();
+        let mut overlap = None;
+        for (address, instruction) in &program.instructions {
+            for cell in *address..address + instruction.width() {
+                if owners.insert(cell, *address).is_some() {
+                    overlap = overlap.or(Some(*address));
+                }
+            }
+        }
+        if let Some((address, _)) = &failure {
+            owners.entry(*address).or_insert(*address);
+        }
+
+        let mut reads = HashSet::new();
+        for (address, instruction) in &program.instructions {
+            for (index, parameter) in instruction.parameters.iter().enumerate() {
+                if instruction.modes[index] == POSITION && instruction.operands[index] < 0 {
+                    return Err(format!("Negative address at address {}", address));
+                }
+                let operand = instruction.operands[index] as usize;
+                if *parameter == Parameter::Write {
+                    if owners.contains_key(&operand) {
+                        return Err(format!(
+                            "Program modifies its own code at address {}",
+                            operand
+                        ));
+                    }
+                    program.writers.entry(operand).or_default().push(*address);
+                } else if instruction.reads(index) {
+                    reads.insert(operand);
+                } else if instruction.is_jump() && instruction.modes[index] == POSITION {
+                    program.target_cells.insert(operand);
+                }
+            }
+        }
+
+        if let Some(address) = overlap {
+            return Err(format!(
+                "Address {} is jumped into mid-instruction",
+                address
+            ));
+        }
+        if let Some(address) = reads
+            .iter()
+            .chain(program.target_cells.iter())
+            .find(|address| owners.contains_key(address))
+        {
+            return Err(format!("Program reads its own code at address {}", address));
+        }
+        for cell in &program.target_cells {
+            if program.writers.contains_key(cell) {
+                return Err(format!(
+                    "Jump target at address {} is computed at runtime",
+                    cell
+                ));
+            }
+            if reads.contains(cell) {
+                return Err(format!(
+                    "Jump target at address {} is also used as data",
+                    cell
+                ));
+            }
+        }
+
+        match failure {
+            Some((_, e)) => Err(e),
+            None => Ok(program),
+        }
+    }
+
+    /// Target of a jump, position mode targets are held in never written cells
+    fn target(&self, instruction: &Instruction) -> usize {
+        match instruction.modes[1] {
+            IMMEDIATE => instruction.operands[1] as usize,
+            _ => *self
+                .mem
+                .get(&(instruction.operands[1] as usize))
+                .unwrap_or(&0) as usize,
+        }
+    }
+
+    fn successors(&self, address: usize) -> Vec<usize> {
+        let instruction = &self.instructions[&address];
+        let next = address + instruction.width();
+        if instruction.code == HALT {
+            return Vec::new();
+        }
+        if !instruction.is_jump() {
+            return vec![next];
+        }
+        match instruction.taken() {
+            Some(true) => vec![self.target(instruction)],
+            Some(false) => vec![next],
+            None => vec![next, self.target(instruction)],
+        }
+    }
+
+    /// Dominator sets of every instruction reachable from the start
+    fn dominators(&self) -> HashMap<usize, HashSet<usize>> {
+        let mut order = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pending = vec![0];
+        while let Some(address) = pending.pop() {
+            if seen.insert(address) {
+                order.push(address);
+                pending.extend(self.successors(address));
+            }
+        }
+
+        let mut predecessors: HashMap<usize, Vec<usize>> = HashMap::new();
+        for address in &order {
+            for successor in self.successors(*address) {
+                predecessors.entry(successor).or_default().push(*address);
+            }
+        }
+
+        let all: HashSet<usize> = order.iter().copied().collect();
+        let mut dominators: HashMap<usize, HashSet<usize>> = order
+            .iter()
+            .map(|address| (*address, all.clone()))
+            .collect();
+        dominators.insert(0, [0].iter().copied().collect());
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for address in order.iter().skip(1) {
+                let mut dominated: Option<HashSet<usize>> = None;
+                for predecessor in predecessors.get(address).into_iter().flatten() {
+                    let set = &dominators[predecessor];
+                    dominated = Some(match dominated {
+                        Some(dominated) => dominated.intersection(set).copied().collect(),
+                        None => set.clone(),
+                    });
+                }
+                let mut dominated = dominated.unwrap_or_default();
+                dominated.insert(*address);
+                if dominated != dominators[address] {
+                    dominators.insert(*address, dominated);
+                    changed = true;
+                }
+            }
+        }
+        dominators
+    }
+
+    /// Constant written by the only writer of an address
+    fn constant_writes(&self) -> Vec<(usize, usize, isize)> {
+        self.instructions
+            .iter()
+            .filter(|(address, _)| !self.removed.contains(address))
+            .filter_map(|(address, instruction)| {
+                let value = instruction.constant()?;
+                let destination = instruction.operands[2] as usize;
+                match self.writers[&destination].as_slice() {
+                    [writer] if writer == address => Some((*address, destination, value)),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Replace reads of constant cells dominated by their only writer with the constant
+    fn fold_constants(&mut self) -> usize {
+        let mut folded = 0;
+        loop {
+            let dominators = self.dominators();
+            let mut changed = false;
+            for (writer, destination, value) in self.constant_writes() {
+                for (address, instruction) in self.instructions.iter_mut() {
+                    let dominated = dominators
+                        .get(address)
+                        .is_some_and(|dominators| dominators.contains(&writer));
+                    if *address == writer || !dominated {
+                        continue;
+                    }
+                    for index in 0..instruction.operands.len() {
+                        if instruction.reads(index)
+                            && instruction.operands[index] as usize == destination
+                        {
+                            instruction.modes[index] = IMMEDIATE;
+                            instruction.operands[index] = value;
+                            folded += 1;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+            if !changed {
+                return folded;
+            }
+        }
+    }
+
+    /// Remove no-ops and constant writes nothing reads any more
+    fn remove_dead(&mut self) {
+        let reads: HashSet<usize> = self
+            .instructions
+            .values()
+            .flat_map(|instruction| {
+                (0..instruction.operands.len())
+                    .filter(move |index| instruction.reads(*index))
+                    .map(move |index| instruction.operands[index] as usize)
+            })
+            .collect();
+        let dead: Vec<usize> = self
+            .constant_writes()
+            .into_iter()
+            .filter(|(_, destination, _)| !reads.contains(destination))
+            .map(|(writer, _, _)| writer)
+            .collect();
+        self.removed.extend(dead);
+
+        let noops: Vec<usize> = self
+            .instructions
+            .iter()
+            .filter(|(_, instruction)| instruction.is_noop())
+            .map(|(address, _)| *address)
+            .collect();
+        self.removed.extend(noops);
+    }
+
+    /// First instruction to run from an address, skipping removed instructions
+    fn surviving(&self, mut address: usize) -> usize {
+        while self.removed.contains(&address) {
+            address += self.instructions[&address].width();
+        }
+        address
+    }
+
+    /// Retarget jumps landing on unconditional jumps to the end of the chain
+    fn shorten_jumps(&mut self) -> usize {
+        let mut shortened = 0;
+        let jumps: Vec<usize> = self
+            .instructions
+            .iter()
+            .filter(|(address, instruction)| {
+                instruction.is_jump() && !self.removed.contains(address)
+            })
+            .map(|(address, _)| *address)
+            .collect();
+
+        for jump in jumps {
+            let original = self.target(&self.instructions[&jump]);
+            let mut target = self.surviving(original);
+            let mut visited = HashSet::new();
+            while visited.insert(target) {
+                match self.instructions.get(&target) {
+                    Some(next) if next.taken() == Some(true) => {
+                        target = self.surviving(self.target(next))
+                    }
+                    _ => break,
+                }
+            }
+            if target != original {
+                let instruction = self.instructions.get_mut(&jump).unwrap();
+                instruction.modes[1] = IMMEDIATE;
+                instruction.operands[1] = target as isize;
+                shortened += 1;
+            }
+        }
+        shortened
+    }
+
+    /// Remove jumps that land where they would fall through to anyway
+    fn remove_redundant_jumps(&mut self) {
+        loop {
+            let redundant: Vec<usize> = self
+                .instructions
+                .iter()
+                .filter(|(address, instruction)| {
+                    instruction.is_jump()
+                        && !self.removed.contains(address)
+                        && self.surviving(self.target(instruction))
+                            == self.surviving(*address + instruction.width())
+                })
+                .map(|(address, _)| *address)
+                .collect();
+            if redundant.is_empty() {
+                return;
+            }
+            self.removed.extend(redundant);
+        }
+    }
+
+    /// Lay the program out again without removed instructions, updating every address
+    fn relocate(&self) -> HashMap<usize, isize> {
+        let mut shifts = BTreeMap::new();
+        let mut shift = 0;
+        for (address, instruction) in &self.instructions {
+            if self.removed.contains(address) {
+                shift += instruction.width();
+                shifts.insert(address + instruction.width(), shift);
+            }
+        }
+        let relocate = |address: isize| {
+            let address = address as usize;
+            let shift = shifts
+                .range(..=address)
+                .next_back()
+                .map_or(0, |(_, shift)| *shift);
+            (address - shift) as isize
+        };
+
+        let mut code = HashSet::new();
+        let mut mem = HashMap::new();
+        for (address, instruction) in &self.instructions {
+            code.extend(*address..address + instruction.width());
+            if self.removed.contains(address) {
+                continue;
+            }
+            let mut instruction = instruction.clone();
+            for index in 0..instruction.operands.len() {
+                let is_target = instruction.is_jump() && index == 1;
+                if instruction.modes[index] == POSITION || is_target {
+                    instruction.operands[index] = relocate(instruction.operands[index]);
+                }
+            }
+            let start = relocate(*address as isize) as usize;
+            for (offset, cell) in instruction.encode().into_iter().enumerate() {
+                mem.insert(start + offset, cell);
+            }
+        }
+
+        for (address, value) in self.mem {
+            if code.contains(address) {
+                continue;
+            }
+            let value = if self.target_cells.contains(address) {
+                relocate(*value)
+            } else {
+                *value
+            };
+            mem.insert(relocate(*address as isize) as usize, value);
+        }
+        mem
+    }
+}
+
+/// Optimize a program, failing if it can not be proven safe to rewrite
+/// the optimized program produces the same outputs for the same inputs,
+/// but memory left behind once it halts may differ
+pub fn optimize(mem: &HashMap<usize, isize>) -> Result<(HashMap<usize, isize>, Report), String> {
+    let mut program = Program::analyze(mem)?;
+
+    let folded_reads = program.fold_constants();
+    program.remove_dead();
+    let shortened_jumps = program.shorten_jumps();
+    program.remove_redundant_jumps();
+
+    let report = Report {
+        folded_reads,
+        removed_instructions: program.removed.len(),
+        removed_cells: program
+            .removed
+            .iter()
+            .map(|address| program.instructions[address].width())
+            .sum(),
+        shortened_jumps,
+    };
+    Ok((program.relocate(), report))
+}
+
+/// How a verification run ended
+#[derive(Debug, PartialEq)]
+enum Ending {
+    Halt,
+    NeedsInput,
+    Error,
+}
+
+fn outcome(mem: &HashMap<usize, isize>, inputs: &[isize]) -> (Vec<isize>, Ending) {
+    let mut computer = intcode::Computer::new(mem);
+    computer.enable_loop_detection(LOOP_DETECTION_CAPACITY);
+    let mut inputs = inputs.iter();
+    let mut outputs = Vec::new();
+    loop {
+        match computer.run() {
+            Ok(HaltedState::Halt) => return (outputs, Ending::Halt),
+            Ok(HaltedState::Input) => match inputs.next() {
+                Some(input) => {
+                    if computer.send_input(*input).is_err() {
+                        return (outputs, Ending::Error);
+                    }
+                }
+                None => return (outputs, Ending::NeedsInput),
+            },
+            Ok(HaltedState::Output(output)) => outputs.push(output),
+            Err(_) => return (outputs, Ending::Error),
+        }
+    }
+}
+
+/// Run both programs on each input set, failing on the first set they disagree on
+pub fn verify(
+    original: &HashMap<usize, isize>,
+    optimized: &HashMap<usize, isize>,
+    input_sets: &[Vec<isize>],
+) -> Result<(), String> {
+    for (index, inputs) in input_sets.iter().enumerate() {
+        let expected = outcome(original, inputs);
+        let actual = outcome(optimized, inputs);
+        if expected != actual {
+            return Err(format!(
+                "Input set {} differs: original gave {:?}, optimized gave {:?}",
+                index + 1,
+                expected,
+                actual
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_optimize() {
+        // adds a constant 2 + 3 to an input, with a no-op and a chain of jumps along the way
+        // the last jump in the chain lands where it would fall through to
+        let program = "1101,2,3,30,3,31,1001,31,0,31,1,30,31,32,1105,1,20,99,0,0,\
+                       1105,1,23,4,32,99,0,0,0,0,0,0,0";
+        let mem = intcode::parse_mem(program.as_bytes()).unwrap();
+        let (optimized, report) = optimize(&mem).unwrap();
+
+        assert_eq!(
+            report,
+            Report {
+                folded_reads: 1,
+                removed_instructions: 3,
+                removed_cells: 11,
+                shortened_jumps: 1,
+            }
+        );
+        assert_eq!(optimized.len(), mem.len() - 11);
+        assert_eq!(optimized[&0], 3);
+
+        let input_sets = vec![vec![1], vec![-7], vec![]];
+        verify(&mem, &optimized, &input_sets).unwrap();
+        assert_eq!(outcome(&optimized, &[1]).0, vec![6]);
+    }
+
+    #[test]
+    fn test_overflowing_constants() {
+        // 2^62 * 4 overflows, so is left for the program to compute
+        let mem = intcode::parse_mem("1102,4611686018427387904,4,5,99,0".as_bytes()).unwrap();
+        let (optimized, report) = optimize(&mem).unwrap();
+
+        assert_eq!(report, Report::default());
+        assert_eq!(optimized, mem);
+    }
+
+    #[test]
+    fn test_unsafe_programs() {
+        let error =
+            |program: &str| optimize(&intcode::parse_mem(program.as_bytes()).unwrap()).unwrap_err();
+
+        assert_eq!(
+            error("1,0,0,3,99"),
+            "Program modifies its own code at address 3"
+        );
+        assert_eq!(
+            error("109,5,204,0,99"),
+            "Relative addressing at address 2 cannot be analyzed"
+        );
+        assert_eq!(error("4,1,99"), "Program reads its own code at address 1");
+        assert_eq!(
+            error("1005,10,4,104,99,99"),
+            "Address 4 is jumped into mid-instruction"
+        );
+    }
+}